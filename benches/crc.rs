@@ -0,0 +1,49 @@
+use criterion::{ black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput };
+use uecp_rs::framing::{ compute_crc16_genibus, Crc16 };
+
+// The per-byte bit manipulation the table-driven implementation replaced
+fn crc16_genibus_bitwise(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+
+    for c in data {
+        crc = crc.rotate_left(8);
+        crc ^= *c as u16;
+        crc ^= (crc & 0xFF) >> 4;
+        crc ^= (crc << 8) << 4;
+        crc ^= ((crc & 0xFF) << 4) << 1;
+    }
+
+    crc ^ 0xFFFF
+}
+
+fn crc_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("crc16");
+
+    // Smallest frame, typical frame, largest frame (ADD, SEQ, MEL and message)
+    for size in [8usize, 64, 259].iter() {
+        let data: Vec<u8> = (0..*size).map(|x| (x * 31) as u8).collect();
+        assert_eq!(crc16_genibus_bitwise(&data), compute_crc16_genibus(&data));
+
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::new("bitwise", size), &data, |b, data| {
+            b.iter(|| crc16_genibus_bitwise(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("table", size), &data, |b, data| {
+            b.iter(|| compute_crc16_genibus(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut crc = Crc16::new();
+                for chunk in black_box(data).chunks(16) {
+                    crc.update(chunk);
+                }
+                crc.finalize()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, crc_benchmark);
+criterion_main!(benches);
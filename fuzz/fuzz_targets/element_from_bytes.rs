@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use uecp_rs::elements::TypedElement;
+use uecp_rs::frame_ref::ElementIter;
+use uecp_rs::protocol::MessageElement;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(element) = MessageElement::from_bytes(data) {
+        let _ = TypedElement::decode(&element, None);
+        let _ = element.into_bytes();
+    }
+
+    for element in ElementIter::new(data).flatten() {
+        let _ = TypedElement::decode(&MessageElement::from(element), None);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use uecp_rs::framing::StuffingMode;
+use uecp_rs::protocol::Frame;
+
+fuzz_target!(|data: &[u8]| {
+    for mode in [StuffingMode::Strict, StuffingMode::Lenient].iter() {
+        if let Ok(frame) = Frame::from_bytes_with(data, *mode) {
+            let _ = frame.typed_elements(None);
+            let _ = frame.into_bytes();
+        }
+    }
+});
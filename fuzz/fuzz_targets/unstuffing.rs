@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use uecp_rs::framing::{ StuffingMode, stuff_into, unstuff_into_with };
+
+fuzz_target!(|data: &[u8]| {
+    let mut unstuffed = vec![0u8; data.len()];
+    let mut restuffed = vec![0u8; data.len()];
+
+    if let Ok(length) = unstuff_into_with(data, &mut unstuffed, StuffingMode::Strict) {
+        // Valid stuffing is the only encoding of its content
+        let restuffed_length = stuff_into(&unstuffed[..length], &mut restuffed).unwrap();
+        assert_eq!(&restuffed[..restuffed_length], data);
+    }
+
+    unstuff_into_with(data, &mut unstuffed, StuffingMode::Lenient).unwrap();
+});
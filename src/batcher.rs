@@ -0,0 +1,118 @@
+use alloc::{ vec, vec::Vec };
+use crate::defs::{ GroupType, EncodeError, element_types };
+use crate::framing::MAX_MESSAGE_LENGTH;
+use crate::protocol::{ Frame, MessageElement, SequenceCounter };
+
+// Packs any number of elements into as few frames as possible, keeping their order.
+// Elements pushed as a group always end up in the same frame.
+// Frames are numbered from the SequenceCounter of the link they are sent on.
+pub struct FrameBatcher {
+    site_address: u16,
+    encoder_address: u8,
+    keep_text_tags: bool,
+    rt_plus_group: Option<GroupType>,
+    groups: Vec<Vec<MessageElement>>
+}
+
+impl FrameBatcher {
+    pub fn new(site_address: u16, encoder_address: u8) -> Self {
+        FrameBatcher {
+            site_address,
+            encoder_address,
+            keep_text_tags: false,
+            rt_plus_group: None,
+            groups: vec![]
+        }
+    }
+
+    // Keeps DL Plus commands with the DL message before them, and RT+ tags
+    // (free format groups of the RT+ ODA group type) with the RT before them
+    pub fn keep_text_tags(mut self, rt_plus_group: Option<GroupType>) -> Self {
+        self.keep_text_tags = true;
+        self.rt_plus_group = rt_plus_group;
+        self
+    }
+
+    pub fn push(&mut self, element: MessageElement) {
+        if self.keep_text_tags && self.follows_text(&element) {
+            if let Some(group) = self.groups.last_mut() {
+                group.push(element);
+                return;
+            }
+        }
+
+        self.groups.push(vec![element]);
+    }
+
+    pub fn push_group(&mut self, elements: Vec<MessageElement>) {
+        if !elements.is_empty() {
+            self.groups.push(elements);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|x| x.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn into_frames(mut self, sequence: &mut SequenceCounter) -> Result<Vec<Frame>, EncodeError> {
+        Frame::get_address_field(self.site_address, self.encoder_address)?;
+
+        let mut frames: Vec<Frame> = vec![];
+        let mut current: Vec<MessageElement> = vec![];
+        let mut current_length: usize = 0;
+
+        for group in core::mem::take(&mut self.groups) {
+            let mut group_length: usize = 0;
+            for element in group.iter() {
+                group_length += element.into_bytes()?.len();
+            }
+
+            if group_length > MAX_MESSAGE_LENGTH {
+                return Err(EncodeError::MessageTooLarge);
+            }
+
+            if current_length + group_length > MAX_MESSAGE_LENGTH {
+                frames.push(self.make_frame(sequence, core::mem::take(&mut current)));
+                current_length = 0;
+            }
+
+            current.extend(group);
+            current_length += group_length;
+        }
+
+        if !current.is_empty() {
+            frames.push(self.make_frame(sequence, current));
+        }
+
+        Ok(frames)
+    }
+
+    fn make_frame(&self, sequence: &mut SequenceCounter, elements: Vec<MessageElement>) -> Frame {
+        let mut frame = sequence.next_frame();
+        frame.set_addresses(self.site_address, self.encoder_address);
+        frame.elements = elements;
+        frame
+    }
+
+    fn follows_text(&self, element: &MessageElement) -> bool {
+        let previous = match self.groups.last().and_then(|x| x.last()) {
+            Some(x) => x,
+            None => return false
+        };
+
+        let code = element.element_type.code;
+        let previous_code = previous.element_type.code;
+
+        let rt_plus = match self.rt_plus_group {
+            Some(group) => element.data.first() == Some(&group.as_u8()),
+            None => false
+        };
+
+        (code == element_types::FREE_FORMAT.code && rt_plus && previous_code == element_types::RT.code)
+            || (code == element_types::DAB_DL_COMMAND.code && previous_code == element_types::DAB_DL_MESSAGE.code)
+    }
+}
@@ -0,0 +1,103 @@
+// Byte streams frames travel on, and splitting them back into frames
+use std::io::{ self, Read, Write };
+use std::net::{ TcpListener, TcpStream };
+use std::time::Duration;
+
+use uecp_rs::hex::parse_hex;
+
+pub trait Link: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Link for T {}
+
+// Where to connect to (send) or accept connections on (listen)
+#[derive(Debug, Clone, clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct Endpoint {
+    /// TCP address, e.g. 192.168.1.10:5000
+    #[arg(long)]
+    pub tcp: Option<String>,
+
+    /// Serial port, e.g. /dev/ttyUSB0
+    #[arg(long)]
+    pub serial: Option<String>,
+
+    /// Serial port speed
+    #[arg(long, default_value_t = 9600, requires = "serial")]
+    pub baud: u32
+}
+
+// Reads return TimedOut after `timeout` without data
+pub fn connect(endpoint: &Endpoint, timeout: Duration) -> io::Result<Box<dyn Link>> {
+    if let Some(address) = &endpoint.tcp {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(timeout))?;
+        return Ok(Box::new(stream));
+    }
+
+    open_serial(endpoint, timeout)
+}
+
+pub fn open_serial(endpoint: &Endpoint, timeout: Duration) -> io::Result<Box<dyn Link>> {
+    let path = endpoint.serial.as_deref().unwrap_or_default();
+    let port = serialport::new(path, endpoint.baud)
+        .timeout(timeout)
+        .open()
+        .map_err(io::Error::from)?;
+
+    Ok(Box::new(port))
+}
+
+pub fn bind(address: &str) -> io::Result<TcpListener> {
+    TcpListener::bind(address)
+}
+
+// Collects bytes until they form complete frames. Bytes outside of
+// STA...STP are dropped, which resynchronises after line noise.
+#[derive(Debug, Default)]
+pub struct FrameSplitter {
+    pending: Vec<u8>
+}
+
+impl FrameSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+    }
+
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        loop {
+            let start = match self.pending.iter().position(|x| *x == 0xFE) {
+                Some(start) => start,
+                None => {
+                    self.pending.clear();
+                    return None;
+                }
+            };
+            self.pending.drain(..start);
+
+            // A new STA before STP means the previous frame was cut off
+            let end = self.pending.iter().skip(1).position(|x| *x == 0xFE || *x == 0xFF)? + 1;
+            if self.pending[end] == 0xFE {
+                self.pending.drain(..end);
+                continue;
+            }
+
+            return Some(self.pending.drain(..=end).collect());
+        }
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{:02X}", x)).collect::<Vec<_>>().join(" ")
+}
+
+// Hex text when the input is only hex digits and whitespace, raw bytes otherwise
+pub fn decode_input(input: &[u8]) -> Result<Vec<u8>, String> {
+    match std::str::from_utf8(input) {
+        Ok(text) if text.chars().all(|c| c.is_ascii_hexdigit() || c.is_whitespace()) => parse_hex(text).map_err(|e| e.to_string()),
+        _ => Ok(input.to_vec())
+    }
+}
@@ -0,0 +1,423 @@
+// Command-line tool to build, decode, send and receive UECP frames
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{ self, Read, Write };
+use std::process;
+use std::time::{ Duration, Instant };
+
+use clap::{ Args, Parser, Subcommand, ValueEnum };
+use num_traits::FromPrimitive;
+
+use uecp_rs::defs::{ PTY, DecodeError, ResponseCode, element_types };
+use uecp_rs::ebulatin::to_e1;
+use uecp_rs::elements::radiotext::RadioText;
+use uecp_rs::framing::{ StuffingMode, unstuff_into_with };
+use uecp_rs::hex::parse_hex;
+use uecp_rs::logic::process_incoming_frame;
+use uecp_rs::protocol::{ Frame, MessageElement };
+
+mod link;
+
+use link::{ Endpoint, FrameSplitter, Link };
+
+#[derive(Parser)]
+#[command(name = "uecp", about = "Build, decode, send and receive UECP frames")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a frame and write it to stdout
+    Encode {
+        #[command(flatten)]
+        frame: FrameArgs,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Hex)]
+        format: OutputFormat
+    },
+    /// Read frames (hex or binary) from a file or stdin and dissect them
+    Decode {
+        /// Input file, stdin when omitted or "-"
+        file: Option<String>,
+
+        /// Show every field with its byte offsets
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Keep invalid byte stuffing instead of rejecting the frame
+        #[arg(long)]
+        lenient: bool
+    },
+    /// Send frames to an encoder and wait for its acknowledgements
+    Send {
+        #[command(flatten)]
+        endpoint: Endpoint,
+
+        #[command(flatten)]
+        frame: FrameArgs,
+
+        /// Send the frames (hex or binary) from a file ("-" for stdin) instead
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+
+        /// Do not wait for UECP_ACK
+        #[arg(long)]
+        no_ack: bool,
+
+        /// How long to wait for each acknowledgement, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        timeout: u64
+    },
+    /// Accept frames, log them and acknowledge them like an encoder would
+    Listen {
+        #[command(flatten)]
+        endpoint: Endpoint,
+
+        /// Show every field with its byte offsets
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Do not answer with UECP_ACK
+        #[arg(long)]
+        no_ack: bool
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Hex,
+    Binary,
+    Text
+}
+
+#[derive(Args)]
+struct FrameArgs {
+    #[arg(long, default_value_t = 0)]
+    site: u16,
+
+    #[arg(long, default_value_t = 0)]
+    encoder: u8,
+
+    /// Sequence counter, 0 to disable sequence checking
+    #[arg(long, default_value_t = 0)]
+    seq: u8,
+
+    #[arg(long, default_value_t = 0)]
+    dsn: u8,
+
+    #[arg(long, default_value_t = 0)]
+    psn: u8,
+
+    /// Programme Identification, in hex (e.g. C201)
+    #[arg(long, value_parser = parse_pi)]
+    pi: Option<u16>,
+
+    /// Programme Service name, padded to 8 characters
+    #[arg(long)]
+    ps: Option<String>,
+
+    /// Programme Type number (0-31)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..32))]
+    pty: Option<u8>,
+
+    /// RadioText
+    #[arg(long)]
+    rt: Option<String>,
+
+    /// A/B flag of the RadioText
+    #[arg(long, requires = "rt")]
+    ab: bool,
+
+    /// Any other element, as NAME=HEX (e.g. TA_TP=03)
+    #[arg(long = "element", value_name = "NAME=HEX", value_parser = parse_element)]
+    elements: Vec<(String, Vec<u8>)>
+}
+
+fn parse_pi(value: &str) -> Result<u16, String> {
+    u16::from_str_radix(value, 16).map_err(|e| e.to_string())
+}
+
+fn parse_element(value: &str) -> Result<(String, Vec<u8>), String> {
+    let (name, data) = value.split_once('=').ok_or("expected NAME=HEX")?;
+    if element_types::from_name(name).is_none() {
+        return Err(format!("unknown element type {}", name));
+    }
+
+    Ok((String::from(name), parse_hex(data).map_err(|e| e.to_string())?))
+}
+
+impl FrameArgs {
+    fn build(&self) -> Result<Frame, String> {
+        let mut elements: Vec<MessageElement> = vec![];
+
+        if let Some(pi) = self.pi {
+            elements.push(MessageElement::new(element_types::PI, &pi.to_be_bytes()));
+        }
+
+        if let Some(ps) = &self.ps {
+            let mut data = to_e1(ps);
+            if data.len() > 8 {
+                return Err(format!("PS \"{}\" is longer than 8 characters", ps));
+            }
+            data.resize(8, b' ');
+            elements.push(MessageElement::new(element_types::PS, &data));
+        }
+
+        if let Some(pty) = self.pty {
+            elements.push(PTY::from_u8(pty).ok_or("invalid PTY")?.into());
+        }
+
+        if let Some(rt) = &self.rt {
+            let text = RadioText::new(rt, self.ab).map_err(|e| e.to_string())?;
+            elements.push(MessageElement::try_from(&text).map_err(|e| e.to_string())?);
+        }
+
+        for (name, data) in self.elements.iter() {
+            let element_type = element_types::from_name(name).ok_or("unknown element type")?;
+            elements.push(MessageElement::new(element_type, data));
+        }
+
+        if elements.is_empty() {
+            return Err(String::from("no elements given"));
+        }
+
+        let mut frame = Frame::with_sequence(self.seq);
+        frame.set_addresses(self.site, self.encoder);
+        for mut element in elements {
+            element.set_dsn_psn(self.dsn, self.psn);
+            frame.elements.push(element);
+        }
+
+        Ok(frame)
+    }
+}
+
+fn read_input(file: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut input: Vec<u8> = vec![];
+    match file {
+        None | Some("-") => io::stdin().read_to_end(&mut input).map(|_| ()),
+        Some(path) => fs::read(path).map(|data| input = data)
+    }.map_err(|e| e.to_string())?;
+
+    link::decode_input(&input)
+}
+
+fn split_frames(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut splitter = FrameSplitter::new();
+    splitter.push(bytes);
+    std::iter::from_fn(|| splitter.next_frame()).collect()
+}
+
+fn encode(frame: &FrameArgs, format: OutputFormat) -> Result<(), String> {
+    let frame = frame.build()?;
+    let bytes = frame.into_bytes().map_err(|e| e.to_string())?;
+
+    let mut stdout = io::stdout();
+    match format {
+        OutputFormat::Hex => writeln!(stdout, "{}", link::to_hex(&bytes)),
+        OutputFormat::Binary => stdout.write_all(&bytes),
+        OutputFormat::Text => writeln!(stdout, "{:#}", frame)
+    }.map_err(|e| e.to_string())
+}
+
+fn print_frame(bytes: &[u8], verbose: bool, stuffing: StuffingMode) -> Result<Frame, DecodeError> {
+    match Frame::from_bytes_with(bytes, stuffing) {
+        Ok(frame) if verbose => {
+            println!("{:#}\n", frame);
+            Ok(frame)
+        },
+        Ok(frame) => {
+            println!("{}", frame);
+            Ok(frame)
+        },
+        Err(e) => {
+            println!("{}: {}", link::to_hex(bytes), e);
+            Err(e)
+        }
+    }
+}
+
+fn decode(file: Option<&str>, verbose: bool, lenient: bool) -> Result<(), String> {
+    let stuffing = if lenient { StuffingMode::Lenient } else { StuffingMode::Strict };
+
+    let frames = split_frames(&read_input(file)?);
+    if frames.is_empty() {
+        return Err(String::from("no frame found in input"));
+    }
+
+    let mut failed = false;
+    for bytes in frames.iter() {
+        failed |= print_frame(bytes, verbose, stuffing).is_err();
+    }
+
+    if failed {
+        return Err(String::from("some frames could not be decoded"));
+    }
+    Ok(())
+}
+
+// Waits for the UECP_ACK of the frame with sequence number `sequence`.
+// A positive acknowledgement carries no sequence number and answers the last frame.
+fn wait_for_ack(link: &mut dyn Link, splitter: &mut FrameSplitter, sequence: u8, timeout: Duration) -> Result<ResponseCode, String> {
+    let deadline = Instant::now() + timeout;
+    let mut buffer = [0u8; 512];
+
+    loop {
+        while let Some(bytes) = splitter.next_frame() {
+            let frame = match Frame::from_bytes(&bytes) {
+                Ok(frame) => frame,
+                Err(_) => continue
+            };
+
+            let ack = frame.elements.iter()
+                .find(|x| x.element_type.code == element_types::UECP_ACK.code && x.data.get(1).map_or(true, |seq| *seq == sequence));
+            if let Some(ack) = ack {
+                return ResponseCode::from_u8(ack.data[0]).ok_or_else(|| format!("unknown response code {}", ack.data[0]));
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(String::from("timed out waiting for UECP_ACK"));
+        }
+
+        match link.read(&mut buffer) {
+            Ok(0) => return Err(String::from("connection closed")),
+            Ok(length) => splitter.push(&buffer[..length]),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut || e.kind() == io::ErrorKind::WouldBlock => {},
+            Err(e) => return Err(e.to_string())
+        }
+    }
+}
+
+fn send(endpoint: &Endpoint, frame: &FrameArgs, from: Option<&str>, no_ack: bool, timeout: u64) -> Result<(), String> {
+    let frames: Vec<Vec<u8>> = match from {
+        Some(file) => split_frames(&read_input(Some(file))?),
+        None => vec![frame.build()?.into_bytes().map_err(|e| e.to_string())?]
+    };
+
+    let timeout = Duration::from_millis(timeout);
+    let mut link = link::connect(endpoint, timeout).map_err(|e| e.to_string())?;
+    let mut splitter = FrameSplitter::new();
+
+    for bytes in frames.iter() {
+        let frame = Frame::from_bytes_with(bytes, StuffingMode::Lenient).map_err(|e| e.to_string())?;
+        link.write_all(bytes).map_err(|e| e.to_string())?;
+        println!("> {}", frame);
+
+        if no_ack {
+            continue;
+        }
+
+        let response = wait_for_ack(link.as_mut(), &mut splitter, frame.sequence_counter, timeout)?;
+        println!("< {:?}", response);
+        if response != ResponseCode::Ok {
+            return Err(format!("encoder answered {:?}", response));
+        }
+    }
+
+    Ok(())
+}
+
+// Logs and acknowledges frames until the link closes
+// Negative acknowledgement of a frame that failed to decode, when the error has a
+// response code and the address and sequence number come before the damage
+fn error_response(bytes: &[u8], error: DecodeError) -> Option<Frame> {
+    let (code, end) = match error {
+        DecodeError::CRCError => (ResponseCode::CRCError, bytes.len() - 1),
+        DecodeError::BadStuffing { position } => (ResponseCode::BadStuffing, position),
+        _ => return None
+    };
+
+    // ADD and SEQ, from the bytes between STA and the damage
+    let mut header = vec![0u8; end];
+    let length = unstuff_into_with(&bytes[1..end], &mut header, StuffingMode::Strict).ok()?;
+    if length < 3 {
+        return None;
+    }
+
+    let address = u16::from_be_bytes([header[0], header[1]]);
+    let request = Frame {
+        sequence_counter: header[2],
+        site_address: address >> 6,
+        encoder_address: (address & 0x3F) as u8,
+        elements: vec![]
+    };
+    process_incoming_frame(&request, |_| code)
+}
+
+fn serve(link: &mut dyn Link, verbose: bool, no_ack: bool) -> io::Result<()> {
+    let mut splitter = FrameSplitter::new();
+    let mut buffer = [0u8; 512];
+
+    loop {
+        let length = match link.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(length) => length,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut || e.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(e) => return Err(e)
+        };
+        splitter.push(&buffer[..length]);
+
+        while let Some(bytes) = splitter.next_frame() {
+            let decoded = print_frame(&bytes, verbose, StuffingMode::Strict);
+            if no_ack {
+                continue;
+            }
+
+            let response = match decoded {
+                Ok(frame) => process_incoming_frame(&frame, |_| ResponseCode::Ok),
+                Err(e) => error_response(&bytes, e)
+            };
+
+            if let Some(response) = response {
+                if let Ok(response) = response.into_bytes() {
+                    link.write_all(&response)?;
+                }
+            }
+        }
+    }
+}
+
+fn listen(endpoint: &Endpoint, verbose: bool, no_ack: bool) -> Result<(), String> {
+    if let Some(address) = &endpoint.tcp {
+        let listener = link::bind(address).map_err(|e| e.to_string())?;
+        // The bound address, which tells the port when listening on port 0
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+        eprintln!("listening on {}", address);
+
+        for stream in listener.incoming() {
+            let mut stream = stream.map_err(|e| e.to_string())?;
+            let peer = stream.peer_addr().map(|x| x.to_string()).unwrap_or_default();
+            eprintln!("connection from {}", peer);
+
+            if let Err(e) = serve(&mut stream, verbose, no_ack) {
+                eprintln!("connection from {} failed: {}", peer, e);
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut port = link::open_serial(endpoint, Duration::from_secs(1)).map_err(|e| e.to_string())?;
+    serve(port.as_mut(), verbose, no_ack).map_err(|e| e.to_string())
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Encode { frame, format } => encode(frame, *format),
+        Command::Decode { file, verbose, lenient } => decode(file.as_deref(), *verbose, *lenient),
+        Command::Send { endpoint, frame, from, no_ack, timeout } => {
+            send(endpoint, frame, from.as_deref(), *no_ack, *timeout)
+        },
+        Command::Listen { endpoint, verbose, no_ack } => listen(endpoint, *verbose, *no_ack)
+    };
+
+    if let Err(e) = result {
+        eprintln!("uecp: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,233 @@
+use core::convert::TryFrom;
+use alloc::{ vec, vec::Vec };
+use crate::defs::{ DSNPSNType, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+
+// Number of one specific data set, 1 to 253
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "u8", into = "u8"))]
+pub struct DatasetId(u8);
+
+impl DatasetId {
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for DatasetId {
+    type Error = EncodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01..=0xFD => Ok(DatasetId(value)),
+            _ => Err(EncodeError::InvalidDatasetNumber)
+        }
+    }
+}
+
+impl From<DatasetId> for u8 {
+    fn from(value: DatasetId) -> Self {
+        value.0
+    }
+}
+
+// DSN field semantics: 0 is the current data set, 254 all but the current one, 255 all of them
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DatasetNumber {
+    Current,
+    Specific(DatasetId),
+    AllExceptCurrent,
+    All
+}
+
+impl DatasetNumber {
+    pub fn specific(number: u8) -> Result<Self, EncodeError> {
+        DatasetId::try_from(number).map(DatasetNumber::Specific)
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            DatasetNumber::Current => 0x00,
+            DatasetNumber::Specific(x) => x.get(),
+            DatasetNumber::AllExceptCurrent => 0xFE,
+            DatasetNumber::All => 0xFF
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0x00 => DatasetNumber::Current,
+            0xFE => DatasetNumber::AllExceptCurrent,
+            0xFF => DatasetNumber::All,
+            x => DatasetNumber::Specific(DatasetId(x))
+        }
+    }
+
+    pub fn includes_current(self) -> bool {
+        matches!(self, DatasetNumber::Current | DatasetNumber::All)
+    }
+}
+
+// Number of one programme service, 1 to 254; PSN 0 addresses the data set as a whole
+// (e.g. MAKE_PSN_LIST) and 255 is reserved
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "u8", into = "u8"))]
+pub struct ServiceId(u8);
+
+impl ServiceId {
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for ServiceId {
+    type Error = EncodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01..=0xFE => Ok(ServiceId(value)),
+            _ => Err(EncodeError::InvalidServiceNumber(value))
+        }
+    }
+}
+
+impl From<ServiceId> for u8 {
+    fn from(value: ServiceId) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgrammeService {
+    pub number: ServiceId,
+    pub enabled: bool
+}
+
+// An encoder data set and the programme services (main service and EON services) it holds
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dataset {
+    pub number: DatasetNumber,
+    pub services: Vec<ProgrammeService>
+}
+
+impl Dataset {
+    pub fn new(number: DatasetNumber) -> Self {
+        Dataset {
+            number,
+            services: vec![]
+        }
+    }
+
+    pub fn add_service(&mut self, program_service_number: u8) -> Result<(), EncodeError> {
+        let number = ServiceId::try_from(program_service_number)?;
+        if self.service(program_service_number).is_some() {
+            return Err(EncodeError::DuplicateService(program_service_number));
+        }
+
+        self.services.push(ProgrammeService { number, enabled: true });
+        Ok(())
+    }
+
+    pub fn service(&self, program_service_number: u8) -> Option<&ProgrammeService> {
+        self.services.iter().find(|x| x.number.get() == program_service_number)
+    }
+
+    // Sets the DSN and PSN of an element so it applies to one of this data set's services
+    pub fn target(&self, element: &mut MessageElement, program_service_number: u8) -> Result<(), EncodeError> {
+        ServiceId::try_from(program_service_number)?;
+        if self.service(program_service_number).is_none() {
+            return Err(EncodeError::UnknownService(program_service_number));
+        }
+
+        element.set_dsn_psn(self.number.as_u8(), program_service_number);
+        Ok(())
+    }
+
+    pub fn make_psn_list(&self) -> Result<MessageElement, EncodeError> {
+        if self.services.len() > 254 {
+            return Err(EncodeError::ElementTooLarge);
+        }
+
+        let data: Vec<u8> = self.services.iter().map(|x| x.number.get()).collect();
+        let mut element = MessageElement::new(element_types::MAKE_PSN_LIST, &data);
+        element.set_dsn_psn(self.number.as_u8(), 0);
+        Ok(element)
+    }
+
+    // Enables or disables a service, returning the PSN_TOGGLE command to send
+    pub fn toggle_service(&mut self, program_service_number: u8, enabled: bool) -> Result<MessageElement, EncodeError> {
+        ServiceId::try_from(program_service_number)?;
+        let service = self.services.iter_mut()
+            .find(|x| x.number.get() == program_service_number)
+            .ok_or(EncodeError::UnknownService(program_service_number))?;
+        service.enabled = enabled;
+
+        let mut element = MessageElement::new(element_types::PSN_TOGGLE, &[program_service_number, enabled as u8]);
+        element.set_dsn_psn(self.number.as_u8(), 0);
+        Ok(element)
+    }
+
+    // Only a specific data set can be made the current one
+    pub fn select(&self) -> Result<MessageElement, EncodeError> {
+        match self.number {
+            DatasetNumber::Specific(x) => Ok(MessageElement::new(element_types::DATASET_SELECT, &[x.get()])),
+            _ => Err(EncodeError::InvalidDatasetNumber)
+        }
+    }
+}
+
+impl TryFrom<&MessageElement> for Dataset {
+    type Error = DecodeError;
+
+    // Rebuilds a data set from a MAKE_PSN_LIST command
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        if element.element_type.code != element_types::MAKE_PSN_LIST.code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        let services = element.data.iter()
+            .map(|x| ServiceId::try_from(*x).map(|number| ProgrammeService { number, enabled: true }))
+            .collect::<Result<Vec<ProgrammeService>, EncodeError>>()
+            .map_err(|_| DecodeError::InvalidElementData(element_types::MAKE_PSN_LIST.code))?;
+
+        Ok(Dataset {
+            number: DatasetNumber::from_u8(element.dataset_number),
+            services
+        })
+    }
+}
+
+// Data set and programme service a command applies to, when the element carries them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CommandTarget {
+    pub dataset: Option<DatasetNumber>,
+    pub program_service_number: Option<u8>
+}
+
+pub fn command_target(element: &MessageElement) -> CommandTarget {
+    let code = element.element_type.code;
+
+    // DATASET_SELECT carries its data set in the data field
+    if code == element_types::DATASET_SELECT.code {
+        return CommandTarget {
+            dataset: element.data.first().map(|x| DatasetNumber::from_u8(*x)),
+            program_service_number: None
+        };
+    }
+
+    let dataset = match element.element_type.dsn_psn_type {
+        DSNPSNType::None => None,
+        _ => Some(DatasetNumber::from_u8(element.dataset_number))
+    };
+
+    let program_service_number = match element.element_type.dsn_psn_type {
+        DSNPSNType::All => Some(element.program_service_number),
+        _ if code == element_types::PSN_TOGGLE.code => element.data.first().copied(),
+        _ => None
+    };
+
+    CommandTarget { dataset, program_service_number }
+}
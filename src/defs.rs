@@ -1,9 +1,22 @@
+use core::fmt;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum DecodeError {
     MessageTooShort,
     CRCError,
     UnknownElementType(u8),
     MessageTooLarge,
+    UnexpectedElementType(u8),
+    InvalidElementData(u8),
+    BufferTooSmall,
+    // Position of the offending byte in the stuffed data
+    BadStuffing { position: usize },
+    // Less than the ADD, SEQ, MEL and CRC fields
+    FrameHeaderTruncated,
+    // MEL field of the frame against the bytes found between MEL and CRC
+    MessageLengthMismatch { declared: usize, actual: usize },
+    // Data length of an element against its fixed length or MEL field
+    ElementLengthMismatch { mec: u8, expected: usize, actual: usize }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -11,11 +24,70 @@ pub enum EncodeError {
     ElementTooLarge,
     MessageTooLarge,
     InvalidSiteAddress,
-    InvalidEncoderAddress
+    InvalidEncoderAddress,
+    ParameterOutOfRange,
+    InvalidDatasetNumber,
+    // PSN 0 and 255 can't name a programme service
+    InvalidServiceNumber(u8),
+    // A data set holds each PSN once
+    DuplicateService(u8),
+    // The PSN is not in the data set
+    UnknownService(u8),
+    TextTooLong,
+    BufferTooSmall,
+    // Data length of a fixed-length element
+    ElementLengthMismatch { mec: u8, expected: usize, actual: usize }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::MessageTooShort => write!(f, "message too short"),
+            DecodeError::CRCError => write!(f, "CRC mismatch"),
+            DecodeError::UnknownElementType(mec) => write!(f, "unknown element type 0x{:02X}", mec),
+            DecodeError::MessageTooLarge => write!(f, "message too large"),
+            DecodeError::UnexpectedElementType(mec) => write!(f, "unexpected element type 0x{:02X}", mec),
+            DecodeError::InvalidElementData(mec) => write!(f, "invalid data for element 0x{:02X}", mec),
+            DecodeError::BufferTooSmall => write!(f, "buffer too small"),
+            DecodeError::BadStuffing { position } => write!(f, "bad byte stuffing at offset {}", position),
+            DecodeError::FrameHeaderTruncated => write!(f, "frame header truncated"),
+            DecodeError::MessageLengthMismatch { declared, actual } =>
+                write!(f, "MEL declares {} bytes, frame holds {}", declared, actual),
+            DecodeError::ElementLengthMismatch { mec, expected, actual } =>
+                write!(f, "element 0x{:02X} expects {} data bytes, got {}", mec, expected, actual)
+        }
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::ElementTooLarge => write!(f, "element too large"),
+            EncodeError::MessageTooLarge => write!(f, "message too large"),
+            EncodeError::InvalidSiteAddress => write!(f, "invalid site address"),
+            EncodeError::InvalidEncoderAddress => write!(f, "invalid encoder address"),
+            EncodeError::ParameterOutOfRange => write!(f, "parameter out of range"),
+            EncodeError::InvalidDatasetNumber => write!(f, "invalid dataset number"),
+            EncodeError::InvalidServiceNumber(psn) => write!(f, "invalid programme service number {}", psn),
+            EncodeError::DuplicateService(psn) => write!(f, "service {} is already in the data set", psn),
+            EncodeError::UnknownService(psn) => write!(f, "service {} is not in the data set", psn),
+            EncodeError::TextTooLong => write!(f, "text too long"),
+            EncodeError::BufferTooSmall => write!(f, "buffer too small"),
+            EncodeError::ElementLengthMismatch { mec, expected, actual } =>
+                write!(f, "element 0x{:02X} expects {} data bytes, got {}", mec, expected, actual)
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResponseCode {
     Ok = 0,
     CRCError = 1,
@@ -42,6 +114,7 @@ impl ResponseCode {
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PTY {
     None = 0,
     News = 1,
@@ -83,10 +156,67 @@ impl PTY {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupVersion {
+    A,
+    B
+}
+
+impl GroupVersion {
+    // B0 bit of block 2
+    pub fn as_u8(self) -> u8 {
+        match self {
+            GroupVersion::A => 0,
+            GroupVersion::B => 1
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(GroupVersion::A),
+            1 => Some(GroupVersion::B),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupType {
+    pub number: u8,
+    pub version: GroupVersion
+}
+
+impl GroupType {
+    pub fn new(number: u8, version: GroupVersion) -> Option<Self> {
+        if number > 15 {
+            return None;
+        }
+
+        Some(GroupType { number, version })
+    }
+
+    // Group type code as in the 5 most significant bits of block 2
+    pub fn as_u8(self) -> u8 {
+        (self.number << 1) | self.version.as_u8()
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        if value > 0x1F {
+            return None;
+        }
+
+        GroupType::new(value >> 1, GroupVersion::from_u8(value & 0x01)?)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum LengthType {
     FixedLength(usize),
-    VariableLength
+    VariableLength,
+    // No MEL: from min to max data bytes, up to the end of the message (UECP_ACK)
+    RangeLength(usize, usize)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -110,11 +240,14 @@ impl MessageElementType {
         }
     }
 
+    pub fn name(&self) -> Option<&'static str> {
+        element_types::name_of(self.code)
+    }
+
     pub fn get_next_element_length(bytes: &[u8]) -> Result<usize, DecodeError> {
-        println!("gnel bytes: {:?}", bytes);
-        let first_byte: &u8 = bytes.first().ok_or_else(|| DecodeError::MessageTooShort)?;
+        let first_byte: &u8 = bytes.first().ok_or(DecodeError::MessageTooShort)?;
         let element_type = element_types::from_code(first_byte)
-            .ok_or_else(|| DecodeError::UnknownElementType(*first_byte))?;
+            .ok_or(DecodeError::UnknownElementType(*first_byte))?;
 
         // element layout:
         // - element type (one byte)
@@ -133,15 +266,67 @@ impl MessageElementType {
         Ok(match element_type.length_type {
             // element type, dsn?, psn?, fixed-length data
             LengthType::FixedLength(x) => base_offset + x,
+            // element type, data up to the end of the message
+            LengthType::RangeLength(min, max) => base_offset + bytes.len().saturating_sub(base_offset).clamp(min, max),
             // element type, dsn?, psn?, data length, variable-length data
             LengthType::VariableLength => {
-                let variable_length: &u8 = bytes.get(base_offset).ok_or_else(|| DecodeError::MessageTooShort)?;
+                let variable_length: &u8 = bytes.get(base_offset).ok_or(DecodeError::MessageTooShort)?;
                 base_offset + 1 + ((*variable_length) as usize)
             }
         })
     }
 }
 
+// Serialized by registered name, deserialized from a name or a MEC
+#[cfg(feature = "serde")]
+impl serde::Serialize for MessageElementType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.name() {
+            Some(name) => serializer.serialize_str(name),
+            None => serializer.serialize_u8(self.code)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MessageElementType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ElementTypeVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ElementTypeVisitor {
+            type Value = MessageElementType;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an element type name or MEC")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                element_types::from_name(value)
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                if value > 0xFF {
+                    return Err(E::invalid_value(serde::de::Unexpected::Unsigned(value), &self));
+                }
+
+                element_types::from_code(&(value as u8))
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                if value < 0 {
+                    return Err(E::invalid_value(serde::de::Unexpected::Signed(value), &self));
+                }
+
+                self.visit_u64(value as u64)
+            }
+        }
+
+        deserializer.deserialize_any(ElementTypeVisitor)
+    }
+}
+
 pub mod element_types {
     use crate::defs::{ LengthType, DSNPSNType, MessageElementType };
 
@@ -162,6 +347,10 @@ pub mod element_types {
             static ELEMENT_CODE_TO_MAP: phf::Map<u8, MessageElementType> = phf_map! {
                 $($code => $name),*
             };
+
+            static ELEMENT_NAMES: &[(&str, MessageElementType)] = &[
+                $((stringify!($name), $name)),*
+            ];
         };
     }
 
@@ -201,7 +390,7 @@ pub mod element_types {
 
         { RDS_ON_OFF, 0x1Eu8, DSNPSNType::None, LengthType::FixedLength(1) },
         { RDS_PHASE, 0x22u8, DSNPSNType::None, LengthType::FixedLength(2) },
-        { RDS_LEVEL, 0x0Eu8, DSNPSNType::None, LengthType::FixedLength(2) }, // todo recheck from spec
+        { RDS_LEVEL, 0x0Eu8, DSNPSNType::None, LengthType::FixedLength(2) }, // reference (3 bits), level in mV (13 bits)
 
         { SITE_ADDRESS, 0x23u8, DSNPSNType::None, LengthType::FixedLength(3) },
         { ENCODER_ADDRESS, 0x27u8, DSNPSNType::None, LengthType::FixedLength(2) },
@@ -221,7 +410,7 @@ pub mod element_types {
         { COMM_PORT_SPEED, 0x3Cu8, DSNPSNType::None, LengthType::FixedLength(2) },
         { COMM_PORT_TIMEOUT, 0x3Du8, DSNPSNType::None, LengthType::FixedLength(2) },
 
-        { UECP_ACK, 0x18u8, DSNPSNType::None, LengthType::FixedLength(2) },
+        { UECP_ACK, 0x18u8, DSNPSNType::None, LengthType::RangeLength(1, 2) },
         { UECP_REQUEST, 0x17u8, DSNPSNType::None, LengthType::VariableLength },
 
         { SPECIFIC_COMMAND, 0x2Du8, DSNPSNType::None, LengthType::VariableLength },
@@ -230,6 +419,15 @@ pub mod element_types {
     }
 
     pub fn from_code(code: &u8) -> Option<MessageElementType> {
-        ELEMENT_CODE_TO_MAP.get(code).map(|x| *x)
+        ELEMENT_CODE_TO_MAP.get(code).copied()
+    }
+
+    // Name the element type is registered under, e.g. "RT"
+    pub fn name_of(code: u8) -> Option<&'static str> {
+        ELEMENT_NAMES.iter().find(|(_, x)| x.code == code).map(|(name, _)| *name)
+    }
+
+    pub fn from_name(name: &str) -> Option<MessageElementType> {
+        ELEMENT_NAMES.iter().find(|(x, _)| *x == name).map(|(_, element_type)| *element_type)
     }
 }
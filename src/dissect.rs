@@ -0,0 +1,362 @@
+// Human-readable dissection of frames and elements, for debugging encoders.
+// `{}` prints a one-line summary, e.g. `SEQ=12 SITE=62 ENC=14 | PI dsn=0 psn=0 C201`.
+// `{:#}` prints a tree of every field with its byte offsets, as sent on the wire
+// (after byte stuffing) for frames, and from the MEC for single elements.
+use core::fmt;
+use alloc::{ format, vec, vec::Vec, string::String };
+use crate::defs::{ DSNPSNType, LengthType, EncodeError, GroupType, GroupVersion, element_types };
+use crate::ebulatin::from_e1;
+use crate::elements::TypedElement;
+use crate::elements::radiotext::RtPlusTags;
+use crate::frame_ref::ElementRef;
+use crate::framing;
+use crate::protocol::{ Frame, MessageElement };
+
+// Group RT+ is usually carried in, as in MetadataConfig::default
+const RT_PLUS_GROUP: GroupType = GroupType { number: 11, version: GroupVersion::A };
+
+// Uppercase hex, with the bytes separated by spaces in the alternate form
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if f.alternate() && i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn element_name(element: &MessageElement) -> String {
+    match element.element_type.name() {
+        Some(name) => String::from(name),
+        None => format!("MEC=0x{:02X}", element.element_type.code)
+    }
+}
+
+// Decoded value of an element, or its data in hex when there is no decoder
+fn summary(element: &MessageElement) -> String {
+    use crate::elements::TypedElement as T;
+
+    let code = element.element_type.code;
+    if code == element_types::PS.code || code == element_types::PTYN.code {
+        return format!("{:?}", from_e1(&element.data));
+    }
+
+    let typed = match TypedElement::decode(element, None) {
+        Ok(typed) => typed,
+        Err(e) if element.data.is_empty() => return format!("({})", e),
+        Err(e) => return format!("{} ({})", Hex(&element.data), e)
+    };
+
+    match typed {
+        T::RadioText(x) if x.transmissions > 0 => {
+            format!("AB={} TX={} {:?}", x.ab_flag as u8, x.transmissions, x.text)
+        },
+        T::RadioText(x) => format!("AB={} {:?}", x.ab_flag as u8, x.text),
+        T::DynamicLabel(x) => format!("{:?} {:?}", x.charset, x.text),
+        T::Raw(x) => format!("{}", Hex(&x.data)),
+        // Debug form of the decoded value, without the variant around it
+        typed => {
+            let debug = format!("{:?}", typed);
+            match debug.find('(') {
+                Some(open) if debug.ends_with(')') => String::from(&debug[open + 1..debug.len() - 1]),
+                _ => debug
+            }
+        }
+    }
+}
+
+// Named part of the element data, as (offset in the data, length, name, value)
+type DataField = (usize, usize, &'static str, String);
+
+// Sub-fields of the element data, from its decoded value
+fn data_fields(element: &MessageElement) -> Vec<DataField> {
+    use crate::elements::TypedElement as T;
+
+    let data = &element.data;
+    let code = element.element_type.code;
+    if code == element_types::PS.code || code == element_types::PTYN.code {
+        return vec![(0, data.len(), "TEXT", format!("{:?}", from_e1(data)))];
+    }
+    if code == element_types::PI.code && data.len() == 2 {
+        return vec![(0, 2, "PI", format!(
+            "{} (country {:X}, area {:X}, reference {:02X})", Hex(data), data[0] >> 4, data[0] & 0x0F, data[1]
+        ))];
+    }
+
+    let typed = match TypedElement::decode(element, None) {
+        Ok(typed) => typed,
+        Err(_) => return vec![]
+    };
+
+    match typed {
+        T::RadioText(x) => vec![
+            (0, 1, "CFG", format!("{:02X} (transmissions {}, A/B {})", data[0], x.transmissions, x.ab_flag as u8)),
+            (1, data.len() - 1, "TEXT", format!("{:?}", x.text))
+        ],
+        T::DynamicLabel(x) => vec![
+            (0, 1, "CHSET", format!("{:02X} ({:?})", data[0], x.charset)),
+            (1, data.len() - 1, "TEXT", format!("{:?}", x.text))
+        ],
+        T::DlPlusTags(x) => {
+            let count = (data.len() - 1) / 3;
+            let mut fields = vec![(0, 1, "CMD", format!(
+                "{:02X} (toggle {}, running {}, {} tags)", data[0], x.item_toggle as u8, x.item_running as u8, count
+            ))];
+            // Dummy tags are dropped from the decoded value, so each tag is read at its position
+            for (i, tag) in data[1..].chunks(3).enumerate() {
+                fields.push((1 + i * 3, 3, "TAG", format!(
+                    "{} (type {}, start {}, length {})", Hex(tag), tag[0] & 0x7F, tag[1] & 0x7F, (tag[2] & 0x7F) + 1
+                )));
+            }
+            fields
+        },
+        T::FreeFormat(x) => {
+            let mut fields = vec![
+                (0, 1, "GROUP", format!("{:02X} ({}{:?})", data[0], x.group_type.number, x.group_type.version)),
+                (1, 1, "B2", format!("{:02X}", x.block_2)),
+                (2, 2, "B3", format!("{:04X}", x.block_3)),
+                (4, 2, "B4", format!("{:04X}", x.block_4))
+            ];
+
+            // Other ODAs use the blocks differently, so only the RT+ group gets the tag reading
+            if x.group_type == RT_PLUS_GROUP {
+                let rt_plus = RtPlusTags::from_free_format(&x);
+                let tags: Vec<String> = rt_plus.tags.iter()
+                    .map(|tag| format!("type {} start {} length {}", tag.content_type.0, tag.start, tag.length))
+                    .collect();
+                fields.push((1, 5, "RT+", format!(
+                    "toggle {}, running {}, {}", rt_plus.item_toggle as u8, rt_plus.item_running as u8, tags.join(", ")
+                )));
+            }
+
+            fields
+        },
+        T::Pin(x) if x.is_none() => vec![(0, 2, "PIN", String::from("none"))],
+        T::Pin(x) => vec![(0, 2, "PIN", format!("day {} {:02}:{:02}", x.day(), x.hour(), x.minute()))],
+        _ => vec![]
+    }
+}
+
+fn write_element_line(f: &mut fmt::Formatter, element: &MessageElement) -> fmt::Result {
+    f.write_str(&element_name(element))?;
+
+    match element.element_type.dsn_psn_type {
+        DSNPSNType::None => {},
+        DSNPSNType::DSNOnly => write!(f, " dsn={}", element.dataset_number)?,
+        DSNPSNType::All => write!(f, " dsn={} psn={}", element.dataset_number, element.program_service_number)?
+    }
+
+    let summary = summary(element);
+    if !summary.is_empty() {
+        write!(f, " {}", summary)?;
+    }
+
+    Ok(())
+}
+
+// Wire position of every logical byte of a frame or element
+struct ByteMap {
+    ranges: Vec<(usize, usize)>
+}
+
+impl ByteMap {
+    fn identity(length: usize) -> Self {
+        ByteMap { ranges: (0..length).map(|i| (i, i)).collect() }
+    }
+
+    // Everything between STA and STP is byte stuffed, 0xFD starting a two-byte escape
+    fn from_wire(wire: &[u8]) -> Self {
+        let mut ranges = Vec::with_capacity(wire.len());
+        let mut position: usize = 0;
+
+        while position < wire.len() {
+            let stuffed = position > 0 && position < wire.len() - 1 && wire[position] == 0xFD;
+            let width = if stuffed { 2 } else { 1 };
+            ranges.push((position, position + width - 1));
+            position += width;
+        }
+
+        ByteMap { ranges }
+    }
+
+    fn format_range(&self, start: usize, length: usize) -> String {
+        if length == 0 {
+            // Empty field, possibly after the last byte
+            let first = match self.ranges.get(start) {
+                Some(range) => range.0,
+                None => self.ranges.last().map_or(0, |range| range.1 + 1)
+            };
+            return format!("[{}-]", first);
+        }
+
+        let first = self.ranges[start].0;
+        let last = self.ranges[start + length - 1].1;
+
+        if first == last {
+            format!("[{}]", first)
+        } else {
+            format!("[{}-{}]", first, last)
+        }
+    }
+}
+
+// Lines of a dissection tree, written without a trailing newline
+struct Tree<'a> {
+    map: &'a ByteMap,
+    lines: Vec<String>
+}
+
+impl<'a> Tree<'a> {
+    fn new(map: &'a ByteMap) -> Self {
+        Tree { map, lines: vec![] }
+    }
+
+    fn line(&mut self, depth: usize, start: usize, length: usize, text: String) {
+        let range = self.map.format_range(start, length);
+        self.lines.push(format!("{:indent$}{:<10}{}", "", range, text, indent = depth * 2));
+    }
+
+    fn field(&mut self, depth: usize, start: usize, length: usize, name: &str, value: String) {
+        self.line(depth, start, length, format!("{:<5} {}", name, value));
+    }
+
+    // `bytes` is the encoded element, starting at `start` in the map
+    fn element(&mut self, depth: usize, start: usize, element: &MessageElement, bytes: &[u8]) {
+        self.line(depth, start, bytes.len(), format!("{}", ElementLine(element)));
+
+        let mut offset = start;
+        self.field(depth + 1, offset, 1, "MEC", format!("{:02X} ({})", bytes[0], element_name(element)));
+        offset += 1;
+
+        match element.element_type.dsn_psn_type {
+            DSNPSNType::None => {},
+            DSNPSNType::DSNOnly => {
+                self.field(depth + 1, offset, 1, "DSN", format!("{:02X}", element.dataset_number));
+                offset += 1;
+            },
+            DSNPSNType::All => {
+                self.field(depth + 1, offset, 1, "DSN", format!("{:02X}", element.dataset_number));
+                self.field(depth + 1, offset + 1, 1, "PSN", format!("{:02X}", element.program_service_number));
+                offset += 2;
+            }
+        }
+
+        if let LengthType::VariableLength = element.element_type.length_type {
+            self.field(depth + 1, offset, 1, "MEL", format!("{:02X} ({} bytes)", element.data.len(), element.data.len()));
+            offset += 1;
+        }
+
+        self.field(depth + 1, offset, element.data.len(), "DATA", format!("{:#}", Hex(&element.data)));
+        for (start, length, name, value) in data_fields(element) {
+            self.field(depth + 2, offset + start, length, name, value);
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.lines.join("\n"))
+    }
+}
+
+// One-line form of an element, also used as the heading of its tree
+struct ElementLine<'a>(&'a MessageElement);
+
+impl fmt::Display for ElementLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_element_line(f, self.0)
+    }
+}
+
+impl fmt::Display for MessageElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !f.alternate() {
+            return write_element_line(f, self);
+        }
+
+        let bytes = match self.into_bytes() {
+            Ok(bytes) => bytes,
+            Err(e) => return write!(f, "{} (cannot encode: {})", ElementLine(self), e)
+        };
+
+        let map = ByteMap::identity(bytes.len());
+        let mut tree = Tree::new(&map);
+        tree.element(0, 0, self, &bytes);
+        tree.write(f)
+    }
+}
+
+// Offset and length of every element in the unstuffed frame
+type ElementOffsets = Vec<(usize, usize)>;
+
+impl Frame {
+    // Frame as Frame::into_bytes sends it, unstuffed from STA to STP,
+    // with where every byte was on the wire and where the elements are
+    fn dissection_bytes(&self) -> Result<(Vec<u8>, ByteMap, ElementOffsets), EncodeError> {
+        let wire = self.into_bytes()?;
+        let body = &wire[1..wire.len() - 1];
+
+        let mut bytes = vec![0u8; wire.len()];
+        let length = framing::unstuff_into(body, &mut bytes[1..])
+            .expect("encoded frames are correctly stuffed");
+        bytes.truncate(length + 2);
+        bytes[0] = wire[0];
+        bytes[length + 1] = wire[wire.len() - 1];
+
+        // STA, ADD, SEQ and MEL come first, CRC and STP last
+        let crc_offset = bytes.len() - 3;
+        let mut elements: ElementOffsets = vec![];
+        let mut offset = 5;
+        while let Ok((_, element_length)) = ElementRef::parse(&bytes[offset..crc_offset]) {
+            elements.push((offset, element_length));
+            offset += element_length;
+        }
+
+        Ok((bytes, ByteMap::from_wire(&wire), elements))
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SEQ={} SITE={} ENC={}", self.sequence_counter, self.site_address, self.encoder_address)?;
+
+        if !f.alternate() {
+            for element in self.elements.iter() {
+                write!(f, " | {}", ElementLine(element))?;
+            }
+            return Ok(());
+        }
+
+        let (bytes, map, elements) = match self.dissection_bytes() {
+            Ok(x) => x,
+            Err(e) => {
+                write!(f, " (cannot encode: {})", e)?;
+                for element in self.elements.iter() {
+                    write!(f, "\n  {}", ElementLine(element))?;
+                }
+                return Ok(());
+            }
+        };
+
+        let mut tree = Tree::new(&map);
+        let crc_offset = bytes.len() - 3;
+
+        tree.field(1, 0, 1, "STA", String::from("FE"));
+        tree.field(1, 1, 2, "ADD", format!(
+            "{} (site {}, encoder {})", Hex(&bytes[1..3]), self.site_address, self.encoder_address
+        ));
+        tree.field(1, 3, 1, "SEQ", format!("{:02X} ({})", bytes[3], bytes[3]));
+        tree.field(1, 4, 1, "MEL", format!("{:02X} ({} bytes)", bytes[4], bytes[4]));
+        for ((offset, length), element) in elements.iter().zip(self.elements.iter()) {
+            tree.element(1, *offset, element, &bytes[*offset..*offset + *length]);
+        }
+        tree.field(1, crc_offset, 2, "CRC", format!("{}", Hex(&bytes[crc_offset..crc_offset + 2])));
+        tree.field(1, bytes.len() - 1, 1, "STP", String::from("FF"));
+
+        writeln!(f)?;
+        tree.write(f)
+    }
+}
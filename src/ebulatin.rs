@@ -1,5 +1,27 @@
 use phf::phf_map;
-use std::str;
+#[cfg(feature = "alloc")]
+use alloc::{ string::String, vec::Vec };
+use crate::defs::EncodeError;
+
+// Reverse of E1, indexed by code; codes missing from E1 decode to the same code point
+static E1_DECODE: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{04}', '\u{05}', '\u{06}', '\u{07}', '\u{08}', '\u{09}', '\u{0A}', '\u{0B}', '\u{0C}', '\u{0D}', '\u{0E}', '\u{0F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{14}', '\u{15}', '\u{16}', '\u{17}', '\u{18}', '\u{19}', '\u{1A}', '\u{1B}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '―', '_',
+    '║', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '¯', '\u{7F}',
+    'á', 'à', 'é', 'è', 'í', 'ì', 'ó', 'ò', 'ú', 'ù', 'Ñ', 'Ç', 'Ş', 'ß', '¡', 'Ĳ',
+    'â', 'ä', 'ê', 'ë', 'î', 'ï', 'ô', 'ö', 'û', 'ü', 'ñ', 'ç', 'ş', 'ğ', 'ı', 'ĳ',
+    'ª', 'α', '©', '‰', 'Ğ', 'ě', 'ň', 'ő', 'π', '€', '£', '$', '←', '↑', '→', '↓',
+    'º', '¹', '²', '³', '±', 'İ', 'ń', 'ű', 'µ', '¿', '÷', '°', '¼', '½', '¾', '§',
+    'Á', 'À', 'É', 'È', 'Í', 'Ì', 'Ó', 'Ò', 'Ú', 'Ù', 'Ř', 'Č', 'Š', 'Ž', 'Ð', 'Ŀ',
+    'Â', 'Ä', 'Ê', 'Ë', 'Î', 'Ï', 'Ô', 'Ö', 'Û', 'Ü', 'ř', 'č', 'š', 'ž', 'đ', 'ŀ',
+    'Ã', 'Å', 'Æ', 'Œ', 'ŷ', 'Ý', 'Õ', 'Ø', 'Ꝥ', 'Ŋ', 'Ŕ', 'Ć', 'Ś', 'Ź', 'Ŧ', 'ð',
+    'ã', 'å', 'æ', 'œ', 'ŵ', 'ý', 'õ', 'ø', 'ꝧ', 'ŋ', 'ŕ', 'ć', 'ś', 'ź', 'ŧ', 'ÿ'
+];
 
 static E1: phf::Map<char, u8> = phf_map! { 
     ' ' => 0x20, '!' => 0x21, '"' => 0x22, '#' => 0x23, '¤' => 0x24, '%' => 0x25, '&' => 0x26, '\'' => 0x27, '(' => 0x28, ')' => 0x29, '*' => 0x2A, '+' => 0x2B,  ',' => 0x2C, '-' => 0x2D, '.' => 0x2E, '/' => 0x2F,
@@ -31,11 +53,40 @@ static E1: phf::Map<char, u8> = phf_map! {
     'ã' => 0xF0, 'å' => 0xF1, 'æ' => 0xF2, 'œ' => 0xF3, 'ŵ' => 0xF4, 'ý' => 0xF5, 'õ' => 0xF6,  'ø' => 0xF7, 'ꝧ' => 0xF8, 'ŋ' => 0xF9, 'ŕ' => 0xFA, 'ć' => 0xFB,  'ś' => 0xFC, 'ź' => 0xFD, 'ŧ' => 0xFE
 };
 
+// Characters missing from the table are truncated to their low byte
+pub fn encode_char(c: char) -> u8 {
+    match E1.get(&c) {
+        Some(x) => *x,
+        None => c as u8
+    }
+}
+
+// Whether a character has its own code, rather than being truncated
+pub fn can_encode(c: char) -> bool {
+    E1.contains_key(&c)
+}
+
+pub fn decode_byte(b: u8) -> char {
+    E1_DECODE[b as usize]
+}
+
+// Returns the number of bytes written to `out`
+pub fn to_e1_into(source: &str, out: &mut [u8]) -> Result<usize, EncodeError> {
+    let mut written: usize = 0;
+    for c in source.chars() {
+        *out.get_mut(written).ok_or(EncodeError::BufferTooSmall)? = encode_char(c);
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+#[cfg(feature = "alloc")]
 pub fn to_e1(source: &str) -> Vec<u8> {
-    source.chars().map(|c| {
-        match E1.get(&c) {
-            Some(x) => *x,
-            None => c as u8
-        }
-    }).collect()
+    source.chars().map(encode_char).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub fn from_e1(source: &[u8]) -> String {
+    source.iter().map(|b| decode_byte(*b)).collect()
 }
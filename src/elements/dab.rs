@@ -0,0 +1,215 @@
+use core::convert::TryFrom;
+use alloc::{ string::{ String, ToString }, vec, vec::Vec };
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::ebulatin::{ to_e1, from_e1 };
+use crate::elements::tags::{ ContentType, Tag };
+
+pub const MAX_DYNAMIC_LABEL_LENGTH: usize = 128;
+
+// DAB character sets (ETSI TS 101 756)
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DabCharset {
+    EbuLatin = 0x00,
+    Ucs2 = 0x06,
+    Utf8 = 0x0F
+}
+
+impl DabCharset {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            DabCharset::EbuLatin => to_e1(text),
+            DabCharset::Ucs2 => text.encode_utf16().flat_map(|x| x.to_be_bytes().to_vec()).collect(),
+            DabCharset::Utf8 => text.as_bytes().to_vec()
+        }
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            DabCharset::EbuLatin => from_e1(bytes),
+            DabCharset::Ucs2 => {
+                let units: Vec<u16> = bytes.chunks(2)
+                    .map(|x| u16::from_be_bytes([x[0], *x.get(1).unwrap_or(&0)]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            },
+            DabCharset::Utf8 => String::from_utf8_lossy(bytes).into_owned()
+        }
+    }
+}
+
+// Dynamic Label: charset byte followed by up to 128 bytes of text
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "DynamicLabelFields", try_from = "DynamicLabelFields")
+)]
+pub struct DynamicLabel {
+    pub text: String,
+    pub charset: DabCharset
+}
+
+// Serialized form, with the text both decoded and encoded in its charset.
+// When deserializing, the raw bytes win over the text if both are given,
+// and both are checked like DynamicLabel::new.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DynamicLabelFields {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    raw: Option<Vec<u8>>,
+    charset: DabCharset
+}
+
+#[cfg(feature = "serde")]
+impl From<DynamicLabel> for DynamicLabelFields {
+    fn from(value: DynamicLabel) -> Self {
+        DynamicLabelFields {
+            raw: Some(value.charset.encode(&value.text)),
+            text: value.text,
+            charset: value.charset
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DynamicLabelFields> for DynamicLabel {
+    type Error = EncodeError;
+
+    fn try_from(value: DynamicLabelFields) -> Result<Self, Self::Error> {
+        let text = match value.raw {
+            Some(raw) if raw.len() > MAX_DYNAMIC_LABEL_LENGTH => return Err(EncodeError::TextTooLong),
+            Some(raw) => value.charset.decode(&raw),
+            None => value.text
+        };
+
+        DynamicLabel::new(&text, value.charset)
+    }
+}
+
+impl DynamicLabel {
+    pub fn new(text: &str, charset: DabCharset) -> Result<Self, EncodeError> {
+        if charset.encode(text).len() > MAX_DYNAMIC_LABEL_LENGTH {
+            return Err(EncodeError::TextTooLong);
+        }
+
+        Ok(DynamicLabel { text: text.to_string(), charset })
+    }
+}
+
+impl TryFrom<&DynamicLabel> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: &DynamicLabel) -> Result<Self, Self::Error> {
+        let text = value.charset.encode(&value.text);
+        if text.len() > MAX_DYNAMIC_LABEL_LENGTH {
+            return Err(EncodeError::TextTooLong);
+        }
+
+        let mut data: Vec<u8> = vec![value.charset.as_u8()];
+        data.extend(text);
+        Ok(MessageElement::new(element_types::DAB_DL_MESSAGE, &data))
+    }
+}
+
+impl TryFrom<&MessageElement> for DynamicLabel {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        if element.element_type.code != element_types::DAB_DL_MESSAGE.code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        let invalid = DecodeError::InvalidElementData(element_types::DAB_DL_MESSAGE.code);
+        let (charset, text) = element.data.split_first().ok_or(invalid)?;
+        let charset = num_traits::FromPrimitive::from_u8(*charset).ok_or(invalid)?;
+
+        Ok(DynamicLabel { text: DabCharset::decode(charset, text), charset })
+    }
+}
+
+// DL Plus tags command (ETSI TS 102 980): CId = 0 (b7-b4), item toggle (b3),
+// item running (b2), number of tags minus one (b1-b0), then 3 bytes per tag
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DlPlusTags {
+    pub item_toggle: bool,
+    pub item_running: bool,
+    pub tags: Vec<Tag>
+}
+
+impl DlPlusTags {
+    pub fn new(item_toggle: bool, item_running: bool, tags: &[Tag]) -> Result<Self, EncodeError> {
+        if tags.len() > 4 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(DlPlusTags { item_toggle, item_running, tags: tags.to_vec() })
+    }
+}
+
+impl TryFrom<&DlPlusTags> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: &DlPlusTags) -> Result<Self, Self::Error> {
+        if value.tags.len() > 4 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        // At least one tag has to be sent, so an empty list becomes a dummy tag
+        let dummy = [Tag { content_type: ContentType::DUMMY, start: 0, length: 1 }];
+        let tags: &[Tag] = if value.tags.is_empty() { &dummy } else { &value.tags };
+
+        let mut data: Vec<u8> = vec![
+            (value.item_toggle as u8) << 3 | (value.item_running as u8) << 2 | (tags.len() - 1) as u8
+        ];
+
+        for tag in tags {
+            let length_marker = tag.length.saturating_sub(1);
+            if tag.content_type.0 > 0x7F || tag.start > 0x7F || length_marker > 0x7F {
+                return Err(EncodeError::ParameterOutOfRange);
+            }
+
+            data.extend_from_slice(&[tag.content_type.0, tag.start, length_marker]);
+        }
+
+        Ok(MessageElement::new(element_types::DAB_DL_COMMAND, &data))
+    }
+}
+
+impl TryFrom<&MessageElement> for DlPlusTags {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        if element.element_type.code != element_types::DAB_DL_COMMAND.code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        let invalid = DecodeError::InvalidElementData(element_types::DAB_DL_COMMAND.code);
+        let (header, body) = element.data.split_first().ok_or(invalid)?;
+
+        let tag_count = (header & 0x03) as usize + 1;
+        if header >> 4 != 0 || body.len() != tag_count * 3 {
+            return Err(invalid);
+        }
+
+        let tags = body.chunks(3)
+            .map(|x| Tag { content_type: ContentType(x[0] & 0x7F), start: x[1] & 0x7F, length: (x[2] & 0x7F) + 1 })
+            .filter(|x| x.content_type != ContentType::DUMMY)
+            .collect();
+
+        Ok(DlPlusTags {
+            item_toggle: header & 0x08 != 0,
+            item_running: header & 0x04 != 0,
+            tags
+        })
+    }
+}
@@ -0,0 +1,322 @@
+use core::convert::TryFrom;
+use num_traits::FromPrimitive;
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressListOperation {
+    Remove = 0,
+    Add = 1,
+    // Removes every address of the list except 0
+    ClearAll = 2
+}
+
+impl AddressListOperation {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SiteAddressCommand {
+    pub operation: AddressListOperation,
+    pub address: u16
+}
+
+impl SiteAddressCommand {
+    pub fn add(address: u16) -> Result<Self, EncodeError> {
+        Self::new(AddressListOperation::Add, address)
+    }
+
+    pub fn remove(address: u16) -> Result<Self, EncodeError> {
+        Self::new(AddressListOperation::Remove, address)
+    }
+
+    pub fn clear_all() -> Self {
+        SiteAddressCommand { operation: AddressListOperation::ClearAll, address: 0 }
+    }
+
+    fn new(operation: AddressListOperation, address: u16) -> Result<Self, EncodeError> {
+        if address > 1023 {
+            return Err(EncodeError::InvalidSiteAddress);
+        }
+
+        Ok(SiteAddressCommand { operation, address })
+    }
+}
+
+impl TryFrom<SiteAddressCommand> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: SiteAddressCommand) -> Result<Self, Self::Error> {
+        let command = SiteAddressCommand::new(value.operation, value.address)?;
+        let address = command.address.to_be_bytes();
+        Ok(MessageElement::new(element_types::SITE_ADDRESS, &[command.operation.as_u8(), address[0], address[1]]))
+    }
+}
+
+impl TryFrom<&MessageElement> for SiteAddressCommand {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::SITE_ADDRESS)?;
+        let invalid = DecodeError::InvalidElementData(element_types::SITE_ADDRESS.code);
+
+        let operation = AddressListOperation::from_u8(data[0]).ok_or(invalid)?;
+        SiteAddressCommand::new(operation, u16::from_be_bytes([data[1], data[2]])).map_err(|_| invalid)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncoderAddressCommand {
+    pub operation: AddressListOperation,
+    pub address: u8
+}
+
+impl EncoderAddressCommand {
+    pub fn add(address: u8) -> Result<Self, EncodeError> {
+        Self::new(AddressListOperation::Add, address)
+    }
+
+    pub fn remove(address: u8) -> Result<Self, EncodeError> {
+        Self::new(AddressListOperation::Remove, address)
+    }
+
+    pub fn clear_all() -> Self {
+        EncoderAddressCommand { operation: AddressListOperation::ClearAll, address: 0 }
+    }
+
+    fn new(operation: AddressListOperation, address: u8) -> Result<Self, EncodeError> {
+        if address > 63 {
+            return Err(EncodeError::InvalidEncoderAddress);
+        }
+
+        Ok(EncoderAddressCommand { operation, address })
+    }
+}
+
+impl TryFrom<EncoderAddressCommand> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: EncoderAddressCommand) -> Result<Self, Self::Error> {
+        let command = EncoderAddressCommand::new(value.operation, value.address)?;
+        Ok(MessageElement::new(element_types::ENCODER_ADDRESS, &[command.operation.as_u8(), command.address]))
+    }
+}
+
+impl TryFrom<&MessageElement> for EncoderAddressCommand {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::ENCODER_ADDRESS)?;
+        let invalid = DecodeError::InvalidElementData(element_types::ENCODER_ADDRESS.code);
+
+        let operation = AddressListOperation::from_u8(data[0]).ok_or(invalid)?;
+        EncoderAddressCommand::new(operation, data[1]).map_err(|_| invalid)
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CommMode {
+    Unidirectional = 0,
+    // The encoder only answers when asked to (UECP_REQUEST)
+    BidirectionalRequested = 1,
+    // The encoder acknowledges every message on its own
+    BidirectionalSpontaneous = 2
+}
+
+impl CommMode {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<CommMode> for MessageElement {
+    fn from(value: CommMode) -> Self {
+        MessageElement::new(element_types::COMM_MODE, &[value.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for CommMode {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::COMM_MODE)?;
+        CommMode::from_u8(data[0]).ok_or(DecodeError::InvalidElementData(element_types::COMM_MODE.code))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommPortMode {
+    pub port: u8,
+    pub enabled: bool
+}
+
+impl From<CommPortMode> for MessageElement {
+    fn from(value: CommPortMode) -> Self {
+        MessageElement::new(element_types::COMM_PORT_MODE, &[value.port, value.enabled as u8])
+    }
+}
+
+impl TryFrom<&MessageElement> for CommPortMode {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::COMM_PORT_MODE)?;
+
+        match data[1] {
+            0 | 1 => Ok(CommPortMode { port: data[0], enabled: data[1] == 1 }),
+            _ => Err(DecodeError::InvalidElementData(element_types::COMM_PORT_MODE.code))
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BaudRate {
+    Baud300 = 0,
+    Baud600 = 1,
+    Baud1200 = 2,
+    Baud2400 = 3,
+    Baud4800 = 4,
+    Baud9600 = 5,
+    Baud19200 = 6,
+    Baud38400 = 7,
+    Baud57600 = 8,
+    Baud115200 = 9
+}
+
+impl BaudRate {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn bits_per_second(self) -> u32 {
+        match self {
+            BaudRate::Baud300 => 300,
+            BaudRate::Baud600 => 600,
+            BaudRate::Baud1200 => 1200,
+            BaudRate::Baud2400 => 2400,
+            BaudRate::Baud4800 => 4800,
+            BaudRate::Baud9600 => 9600,
+            BaudRate::Baud19200 => 19200,
+            BaudRate::Baud38400 => 38400,
+            BaudRate::Baud57600 => 57600,
+            BaudRate::Baud115200 => 115200
+        }
+    }
+
+    pub fn from_bits_per_second(value: u32) -> Option<Self> {
+        (0..=9).filter_map(BaudRate::from_u8).find(|x| x.bits_per_second() == value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommPortSpeed {
+    pub port: u8,
+    pub speed: BaudRate
+}
+
+impl From<CommPortSpeed> for MessageElement {
+    fn from(value: CommPortSpeed) -> Self {
+        MessageElement::new(element_types::COMM_PORT_SPEED, &[value.port, value.speed.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for CommPortSpeed {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::COMM_PORT_SPEED)?;
+        let speed = BaudRate::from_u8(data[1]).ok_or(DecodeError::InvalidElementData(element_types::COMM_PORT_SPEED.code))?;
+        Ok(CommPortSpeed { port: data[0], speed })
+    }
+}
+
+// Time without traffic, in minutes, after which the port falls back to its default state (0 disables it)
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommPortTimeout {
+    pub port: u8,
+    pub minutes: u8
+}
+
+impl From<CommPortTimeout> for MessageElement {
+    fn from(value: CommPortTimeout) -> Self {
+        MessageElement::new(element_types::COMM_PORT_TIMEOUT, &[value.port, value.minutes])
+    }
+}
+
+impl TryFrom<&MessageElement> for CommPortTimeout {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::COMM_PORT_TIMEOUT)?;
+        Ok(CommPortTimeout { port: data[0], minutes: data[1] })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessRights(pub u8);
+
+impl AccessRights {
+    pub const NONE: AccessRights = AccessRights(0x00);
+    pub const READ: AccessRights = AccessRights(0x01);
+    pub const WRITE: AccessRights = AccessRights(0x02);
+    pub const READ_WRITE: AccessRights = AccessRights(0x03);
+
+    pub fn contains(self, other: AccessRights) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_valid(self) -> bool {
+        self.0 & !Self::READ_WRITE.0 == 0
+    }
+}
+
+// Access rights of a communication port on a data set (DSN 255 for all of them)
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncoderAccessRight {
+    pub port: u8,
+    pub dataset_number: u8,
+    pub rights: AccessRights
+}
+
+impl TryFrom<EncoderAccessRight> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: EncoderAccessRight) -> Result<Self, Self::Error> {
+        if !value.rights.is_valid() {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(MessageElement::new(element_types::ENCODER_ACCESS_RIGHT, &[value.port, value.dataset_number, value.rights.0]))
+    }
+}
+
+impl TryFrom<&MessageElement> for EncoderAccessRight {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::ENCODER_ACCESS_RIGHT)?;
+
+        let rights = AccessRights(data[2]);
+        if !rights.is_valid() {
+            return Err(DecodeError::InvalidElementData(element_types::ENCODER_ACCESS_RIGHT.code));
+        }
+
+        Ok(EncoderAccessRight { port: data[0], dataset_number: data[1], rights })
+    }
+}
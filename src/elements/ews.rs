@@ -0,0 +1,171 @@
+use core::convert::TryFrom;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+use alloc::{ vec, vec::Vec };
+use crate::defs::{ PTY, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+use crate::elements::flags::TrafficFlags;
+use crate::elements::slow_labeling::SlowLabeling;
+
+// Emergency Warning System message, sent in type 9A groups:
+// 5 bits in block 2 followed by the whole of blocks 3 and 4.
+// The meaning of those 37 bits is defined per country.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EwsMessage {
+    pub block_2: u8,
+    pub block_3: u16,
+    pub block_4: u16
+}
+
+impl EwsMessage {
+    pub fn new(block_2: u8, block_3: u16, block_4: u16) -> Result<Self, EncodeError> {
+        if block_2 > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(EwsMessage { block_2, block_3, block_4 })
+    }
+
+    pub fn to_bytes(&self) -> Result<[u8; 5], EncodeError> {
+        if self.block_2 > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let block_3 = self.block_3.to_be_bytes();
+        let block_4 = self.block_4.to_be_bytes();
+        Ok([self.block_2, block_3[0], block_3[1], block_4[0], block_4[1]])
+    }
+}
+
+impl TryFrom<EwsMessage> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: EwsMessage) -> Result<Self, Self::Error> {
+        Ok(MessageElement::new(element_types::EWS, &value.to_bytes()?))
+    }
+}
+
+impl TryFrom<&MessageElement> for EwsMessage {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::EWS)?;
+        EwsMessage::new(
+            data[0],
+            u16::from_be_bytes([data[1], data[2]]),
+            u16::from_be_bytes([data[3], data[4]])
+        ).map_err(|_| DecodeError::InvalidElementData(element_types::EWS.code))
+    }
+}
+
+// An emergency warning on a programme service: the EWS message itself, the EWS channel
+// identification (slow labelling variant 7), PTY 31 (or 30 for tests) and TA.
+// restore_pty and restore_traffic are what the service goes back to once the alert ends.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EwsAlert {
+    pub message: EwsMessage,
+    pub channel: Option<u16>,
+    pub test: bool,
+    pub dataset_number: u8,
+    pub program_service_number: u8,
+    pub restore_pty: PTY,
+    pub restore_traffic: TrafficFlags,
+    pub duration: Duration
+}
+
+impl EwsAlert {
+    pub fn new(message: EwsMessage, restore_pty: PTY, restore_traffic: TrafficFlags, duration: Duration) -> Self {
+        EwsAlert {
+            message,
+            channel: None,
+            test: false,
+            dataset_number: 0,
+            program_service_number: 0,
+            restore_pty,
+            restore_traffic,
+            duration
+        }
+    }
+
+    pub fn alarm_pty(&self) -> PTY {
+        if self.test {
+            PTY::AlarmTest
+        } else {
+            PTY::Alarm
+        }
+    }
+
+    pub fn activation_elements(&self) -> Result<Vec<MessageElement>, EncodeError> {
+        let mut elements: Vec<MessageElement> = vec![];
+
+        if let Some(channel) = self.channel {
+            let mut element = MessageElement::try_from(SlowLabeling::EwsChannel(channel))?;
+            element.set_dsn_psn(self.dataset_number, 0);
+            elements.push(element);
+        }
+
+        elements.push(MessageElement::try_from(self.message)?);
+        elements.push(self.service_element(self.alarm_pty().into()));
+        elements.push(self.service_element(TrafficFlags::TrafficAnnouncement.into()));
+
+        Ok(elements)
+    }
+
+    pub fn revert_elements(&self) -> Vec<MessageElement> {
+        vec![
+            self.service_element(self.restore_pty.into()),
+            self.service_element(self.restore_traffic.into())
+        ]
+    }
+
+    // Returns the elements that raise the alert, along with the revert that must follow it
+    #[cfg(feature = "std")]
+    pub fn start(&self, now: Instant) -> Result<(Vec<MessageElement>, AlarmRevert), EncodeError> {
+        let revert = AlarmRevert {
+            deadline: now + self.duration,
+            elements: Some(self.revert_elements())
+        };
+
+        Ok((self.activation_elements()?, revert))
+    }
+
+    fn service_element(&self, mut element: MessageElement) -> MessageElement {
+        element.set_dsn_psn(self.dataset_number, self.program_service_number);
+        element
+    }
+}
+
+// Pending revert of the alarm PTY and TA, so an alert can't stay on air indefinitely.
+// Poll it from the sending loop; the revert elements are handed out exactly once.
+#[cfg(feature = "std")]
+pub struct AlarmRevert {
+    deadline: Instant,
+    elements: Option<Vec<MessageElement>>
+}
+
+#[cfg(feature = "std")]
+impl AlarmRevert {
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.elements.is_some()
+    }
+
+    pub fn poll(&mut self, now: Instant) -> Option<Vec<MessageElement>> {
+        if now >= self.deadline {
+            self.elements.take()
+        } else {
+            None
+        }
+    }
+
+    // Ends the alert ahead of its deadline
+    pub fn revert_now(&mut self) -> Option<Vec<MessageElement>> {
+        self.elements.take()
+    }
+}
@@ -0,0 +1,134 @@
+use core::convert::TryFrom;
+use num_traits::FromPrimitive;
+use crate::defs::{ DecodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Decoder Identification (DI) switches, one bit each (d0 to d3)
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct DecoderIdentification {
+    pub stereo: bool,
+    pub artificial_head: bool,
+    pub compressed: bool,
+    pub dynamic_pty: bool
+}
+
+impl DecoderIdentification {
+    pub fn as_u8(self) -> u8 {
+        (self.stereo as u8)
+            | (self.artificial_head as u8) << 1
+            | (self.compressed as u8) << 2
+            | (self.dynamic_pty as u8) << 3
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        if value > 0x0F {
+            return None;
+        }
+
+        Some(DecoderIdentification {
+            stereo: value & 0x01 != 0,
+            artificial_head: value & 0x02 != 0,
+            compressed: value & 0x04 != 0,
+            dynamic_pty: value & 0x08 != 0
+        })
+    }
+}
+
+impl From<DecoderIdentification> for MessageElement {
+    fn from(value: DecoderIdentification) -> Self {
+        MessageElement::new(element_types::DI, &[value.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for DecoderIdentification {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::DI)?;
+        DecoderIdentification::from_u8(data[0]).ok_or(DecodeError::InvalidElementData(element_types::DI.code))
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MusicSpeech {
+    Speech = 0,
+    Music = 1
+}
+
+impl MusicSpeech {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<MusicSpeech> for MessageElement {
+    fn from(value: MusicSpeech) -> Self {
+        MessageElement::new(element_types::MS, &[value.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for MusicSpeech {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::MS)?;
+        MusicSpeech::from_u8(data[0]).ok_or(DecodeError::InvalidElementData(element_types::MS.code))
+    }
+}
+
+// TA/TP element: bit 0 is TA, bit 1 is TP
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrafficFlags {
+    // TP=0, TA=0: no traffic information on this service or via EON
+    NoTraffic = 0,
+    // TP=0, TA=1: this service carries EON information about a traffic programme
+    EonTrafficProgramme = 1,
+    // TP=1, TA=0: traffic programme, no announcement on air
+    TrafficProgramme = 2,
+    // TP=1, TA=1: traffic announcement on air
+    TrafficAnnouncement = 3
+}
+
+impl TrafficFlags {
+    pub fn new(tp: bool, ta: bool) -> Self {
+        match (tp, ta) {
+            (false, false) => TrafficFlags::NoTraffic,
+            (false, true) => TrafficFlags::EonTrafficProgramme,
+            (true, false) => TrafficFlags::TrafficProgramme,
+            (true, true) => TrafficFlags::TrafficAnnouncement
+        }
+    }
+
+    pub fn tp(self) -> bool {
+        self.as_u8() & 0x02 != 0
+    }
+
+    pub fn ta(self) -> bool {
+        self.as_u8() & 0x01 != 0
+    }
+
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<TrafficFlags> for MessageElement {
+    fn from(value: TrafficFlags) -> Self {
+        MessageElement::new(element_types::TA_TP, &[value.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for TrafficFlags {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::TA_TP)?;
+        TrafficFlags::from_u8(data[0]).ok_or(DecodeError::InvalidElementData(element_types::TA_TP.code))
+    }
+}
@@ -0,0 +1,61 @@
+use core::convert::TryFrom;
+use crate::defs::{ GroupType, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Free format group: the encoder sends the given group type with these block contents.
+// Element layout: group type code, 5 bits of block 2, block 3, block 4.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeFormat {
+    pub group_type: GroupType,
+    pub block_2: u8,
+    pub block_3: u16,
+    pub block_4: u16
+}
+
+impl FreeFormat {
+    pub fn new(group_type: GroupType, block_2: u8, block_3: u16, block_4: u16) -> Result<Self, EncodeError> {
+        if block_2 > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(FreeFormat { group_type, block_2, block_3, block_4 })
+    }
+
+    pub fn to_bytes(&self) -> Result<[u8; 6], EncodeError> {
+        if self.block_2 > 0x1F || self.group_type.number > 15 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let block_3 = self.block_3.to_be_bytes();
+        let block_4 = self.block_4.to_be_bytes();
+        Ok([self.group_type.as_u8(), self.block_2, block_3[0], block_3[1], block_4[0], block_4[1]])
+    }
+}
+
+impl TryFrom<FreeFormat> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: FreeFormat) -> Result<Self, Self::Error> {
+        Ok(MessageElement::new(element_types::FREE_FORMAT, &value.to_bytes()?))
+    }
+}
+
+impl TryFrom<&MessageElement> for FreeFormat {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::FREE_FORMAT)?;
+
+        match GroupType::from_u8(data[0]) {
+            Some(group_type) if data[1] <= 0x1F => Ok(FreeFormat {
+                group_type,
+                block_2: data[1],
+                block_3: u16::from_be_bytes([data[2], data[3]]),
+                block_4: u16::from_be_bytes([data[4], data[5]])
+            }),
+            _ => Err(DecodeError::InvalidElementData(element_types::FREE_FORMAT.code))
+        }
+    }
+}
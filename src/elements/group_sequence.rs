@@ -0,0 +1,270 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+use alloc::{ vec, vec::Vec, string::{ String, ToString } };
+use crate::defs::{ GroupType, GroupVersion, MessageElementType, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+
+// 1187.5 bit/s, 104 bits per group
+pub const GROUPS_PER_SECOND: f64 = 1187.5 / 104.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GroupTypeError {
+    // Nothing before the version letter
+    MissingNumber,
+    // Group numbers go from 0 to 15
+    InvalidNumber,
+    // The last character is not A or B
+    InvalidVersion
+}
+
+impl fmt::Display for GroupTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GroupTypeError::MissingNumber => write!(f, "missing group number"),
+            GroupTypeError::InvalidNumber => write!(f, "group number must be 0 to 15"),
+            GroupTypeError::InvalidVersion => write!(f, "version must be A or B")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GroupTypeError {}
+
+// A group sequence token that isn't a group type, with its position in the list
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSequenceError {
+    pub index: usize,
+    pub token: String,
+    pub error: GroupTypeError
+}
+
+impl fmt::Display for GroupSequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "group {} (\"{}\"): {}", self.index + 1, self.token, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GroupSequenceError {}
+
+impl FromStr for GroupType {
+    type Err = GroupTypeError;
+
+    // Parses group types written as "0A", "11B"...
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let version = match s.chars().last() {
+            Some('A') | Some('a') => GroupVersion::A,
+            Some('B') | Some('b') => GroupVersion::B,
+            _ => return Err(GroupTypeError::InvalidVersion)
+        };
+
+        let number = &s[..s.len() - 1];
+        if number.is_empty() {
+            return Err(GroupTypeError::MissingNumber);
+        }
+
+        number.parse().ok()
+            .and_then(|number| GroupType::new(number, version))
+            .ok_or(GroupTypeError::InvalidNumber)
+    }
+}
+
+// Service carried by a group type, as used in bandwidth reports
+pub fn group_service(group: GroupType) -> &'static str {
+    match (group.number, group.version) {
+        (0, _) => "Basic tuning and switching (PS, AF)",
+        (1, GroupVersion::A) => "Slow labelling and PIN",
+        (1, GroupVersion::B) => "PIN",
+        (2, _) => "RadioText",
+        (3, GroupVersion::A) => "ODA identification",
+        (4, GroupVersion::A) => "Clock time",
+        (5, _) => "Transparent data channels",
+        (6, _) => "In-house",
+        (8, GroupVersion::A) => "Traffic Message Channel",
+        (9, GroupVersion::A) => "Emergency warning",
+        (10, GroupVersion::A) => "PTYN",
+        (14, _) => "Enhanced Other Networks",
+        (15, GroupVersion::A) => "Long PS",
+        (15, GroupVersion::B) => "Fast basic tuning and switching",
+        _ => "Open data applications"
+    }
+}
+
+// Cyclic sequence of groups sent by the encoder, one group type code per byte
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupSequence {
+    pub groups: Vec<GroupType>
+}
+
+impl GroupSequence {
+    pub fn new(groups: &[GroupType]) -> Self {
+        GroupSequence { groups: groups.to_vec() }
+    }
+
+    // Parses a comma or whitespace separated list, such as "0A, 2A, 0A, 3A, 11A"
+    pub fn parse(source: &str) -> Result<Self, GroupSequenceError> {
+        let groups: Result<Vec<GroupType>, GroupSequenceError> = source
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .enumerate()
+            .map(|(index, token)| GroupType::from_str(token)
+                .map_err(|error| GroupSequenceError { index, token: token.to_string(), error }))
+            .collect();
+
+        groups.map(|groups| GroupSequence { groups })
+    }
+
+    pub fn into_element(&self) -> Result<MessageElement, EncodeError> {
+        self.encode(element_types::GROUP_SEQUENCE)
+    }
+
+    pub fn into_extended_element(&self) -> Result<MessageElement, EncodeError> {
+        self.encode(element_types::EXTENDED_GROUP_SEQUENCE)
+    }
+
+    pub fn bandwidth(&self) -> BandwidthReport {
+        BandwidthReport::new(self)
+    }
+
+    fn encode(&self, element_type: MessageElementType) -> Result<MessageElement, EncodeError> {
+        if self.groups.len() > 254 {
+            return Err(EncodeError::ElementTooLarge);
+        }
+
+        let data: Vec<u8> = self.groups.iter().map(|x| x.as_u8()).collect();
+        Ok(MessageElement::new(element_type, &data))
+    }
+}
+
+impl TryFrom<&MessageElement> for GroupSequence {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let code = element.element_type.code;
+        if code != element_types::GROUP_SEQUENCE.code && code != element_types::EXTENDED_GROUP_SEQUENCE.code {
+            return Err(DecodeError::UnexpectedElementType(code));
+        }
+
+        let groups: Option<Vec<GroupType>> = element.data.iter().map(|x| GroupType::from_u8(*x)).collect();
+        groups
+            .map(|groups| GroupSequence { groups })
+            .ok_or(DecodeError::InvalidElementData(code))
+    }
+}
+
+// Order in which the variants of a group type are sent (e.g. 1A slow labelling variants)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariantCodeSequence {
+    pub group_type: GroupType,
+    pub variants: Vec<u8>
+}
+
+impl VariantCodeSequence {
+    pub fn new(group_type: GroupType, variants: &[u8]) -> Self {
+        VariantCodeSequence { group_type, variants: variants.to_vec() }
+    }
+}
+
+impl TryFrom<&VariantCodeSequence> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: &VariantCodeSequence) -> Result<Self, Self::Error> {
+        if value.variants.len() > 253 {
+            return Err(EncodeError::ElementTooLarge);
+        }
+
+        if value.variants.iter().any(|x| *x > 0x0F) {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let mut data: Vec<u8> = vec![value.group_type.as_u8()];
+        data.extend_from_slice(&value.variants);
+        Ok(MessageElement::new(element_types::GROUP_VARIANT_CODE_SEQUENCE, &data))
+    }
+}
+
+impl TryFrom<&MessageElement> for VariantCodeSequence {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let code = element_types::GROUP_VARIANT_CODE_SEQUENCE.code;
+        if element.element_type.code != code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        let (group_type, variants) = element.data.split_first()
+            .ok_or(DecodeError::InvalidElementData(code))?;
+        if variants.iter().any(|x| *x > 0x0F) {
+            return Err(DecodeError::InvalidElementData(code));
+        }
+
+        Ok(VariantCodeSequence {
+            group_type: GroupType::from_u8(*group_type).ok_or(DecodeError::InvalidElementData(code))?,
+            variants: variants.to_vec()
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandwidthReport {
+    // Group types in order of first appearance in the sequence
+    pub groups_per_second: Vec<(GroupType, f64)>,
+    // Time to send the 8 characters of PS (4 type 0 groups)
+    pub ps_time: Option<Duration>,
+    // Time to send a full RT (16 type 2 groups, 64 characters in 2A or 32 in 2B)
+    pub rt_time: Option<Duration>
+}
+
+impl BandwidthReport {
+    fn new(sequence: &GroupSequence) -> Self {
+        let mut counts: Vec<(GroupType, usize)> = vec![];
+        for group in sequence.groups.iter() {
+            match counts.iter_mut().find(|(x, _)| x == group) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*group, 1))
+            }
+        }
+
+        let total = sequence.groups.len() as f64;
+        let groups_per_second: Vec<(GroupType, f64)> = counts.iter()
+            .map(|(group, count)| (*group, GROUPS_PER_SECOND * (*count as f64) / total))
+            .collect();
+
+        let mut report = BandwidthReport {
+            groups_per_second,
+            ps_time: None,
+            rt_time: None
+        };
+
+        report.ps_time = report.time_for_groups(0, None, 4);
+        report.rt_time = report.time_for_groups(2, None, 16);
+        report
+    }
+
+    pub fn rate_of(&self, number: u8, version: Option<GroupVersion>) -> f64 {
+        self.groups_per_second.iter()
+            .filter(|(x, _)| x.number == number && version.map_or(true, |v| x.version == v))
+            .map(|(_, rate)| rate)
+            .sum()
+    }
+
+    // Time for the AF list (method A) to go around once: each 0A group carries
+    // two codes, and the list starts with a code giving the number of frequencies
+    pub fn af_cycle_time(&self, af_count: usize) -> Option<Duration> {
+        self.time_for_groups(0, Some(GroupVersion::A), (af_count + 2) / 2)
+    }
+
+    fn time_for_groups(&self, number: u8, version: Option<GroupVersion>, groups: usize) -> Option<Duration> {
+        let rate = self.rate_of(number, version);
+        if rate > 0.0 {
+            Some(Duration::from_secs_f64(groups as f64 / rate))
+        } else {
+            None
+        }
+    }
+}
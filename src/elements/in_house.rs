@@ -0,0 +1,94 @@
+use core::convert::TryFrom;
+use crate::defs::{ GroupVersion, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// In-house data, sent in type 6A or 6B groups.
+// Element layout: group version (b0), address (5 bits of block 2), block 3, block 4.
+// Block 3 carries the PI code in 6B groups, so only block 4 is used there.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InHouse {
+    pub version: GroupVersion,
+    pub address: u8,
+    pub block_3: u16,
+    pub block_4: u16
+}
+
+impl InHouse {
+    pub fn new_a(address: u8, payload: u32) -> Result<Self, EncodeError> {
+        Self::check_address(address)?;
+
+        Ok(InHouse {
+            version: GroupVersion::A,
+            address,
+            block_3: (payload >> 16) as u16,
+            block_4: payload as u16
+        })
+    }
+
+    pub fn new_b(address: u8, payload: u16) -> Result<Self, EncodeError> {
+        Self::check_address(address)?;
+
+        Ok(InHouse {
+            version: GroupVersion::B,
+            address,
+            block_3: 0,
+            block_4: payload
+        })
+    }
+
+    pub fn payload(&self) -> u32 {
+        match self.version {
+            GroupVersion::A => ((self.block_3 as u32) << 16) | self.block_4 as u32,
+            GroupVersion::B => self.block_4 as u32
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<[u8; 6], EncodeError> {
+        Self::check_address(self.address)?;
+
+        let block_3 = match self.version {
+            GroupVersion::A => self.block_3.to_be_bytes(),
+            GroupVersion::B => [0, 0]
+        };
+        let block_4 = self.block_4.to_be_bytes();
+
+        Ok([self.version.as_u8(), self.address, block_3[0], block_3[1], block_4[0], block_4[1]])
+    }
+
+    fn check_address(address: u8) -> Result<(), EncodeError> {
+        if address > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<InHouse> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: InHouse) -> Result<Self, Self::Error> {
+        Ok(MessageElement::new(element_types::IH, &value.to_bytes()?))
+    }
+}
+
+impl TryFrom<&MessageElement> for InHouse {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::IH)?;
+
+        let version = GroupVersion::from_u8(data[0]);
+        match version {
+            Some(version) if data[1] <= 0x1F => Ok(InHouse {
+                version,
+                address: data[1],
+                block_3: u16::from_be_bytes([data[2], data[3]]),
+                block_4: u16::from_be_bytes([data[4], data[5]])
+            }),
+            _ => Err(DecodeError::InvalidElementData(element_types::IH.code))
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use core::convert::TryFrom;
+use alloc::vec::Vec;
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Linkage information, laid out as block 3 of a type 14A group (variant 12):
+// LA (b15), EG (b14), ILS (b13), reserved (b12), LSN (b11-b0).
+// The encoder also copies LA into block 3 of type 1A groups.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinkageInfo {
+    pub linkage_actuator: bool,
+    pub extended_generic: bool,
+    pub international: bool,
+    pub set_number: u16
+}
+
+impl LinkageInfo {
+    pub fn new(set_number: u16) -> Result<Self, EncodeError> {
+        if set_number > 0x0FFF {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(LinkageInfo {
+            linkage_actuator: false,
+            extended_generic: false,
+            international: false,
+            set_number
+        })
+    }
+
+    // International linkage sets start the LSN with the PI country nibble
+    pub fn new_international(country: u8, link_id: u8) -> Result<Self, EncodeError> {
+        if country > 0x0F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let mut info = LinkageInfo::new(((country as u16) << 8) | link_id as u16)?;
+        info.international = true;
+        Ok(info)
+    }
+
+    pub fn as_u16(&self) -> Result<u16, EncodeError> {
+        if self.set_number > 0x0FFF {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok((self.linkage_actuator as u16) << 15
+            | (self.extended_generic as u16) << 14
+            | (self.international as u16) << 13
+            | self.set_number)
+    }
+
+    pub fn from_u16(value: u16) -> Self {
+        LinkageInfo {
+            linkage_actuator: value & 0x8000 != 0,
+            extended_generic: value & 0x4000 != 0,
+            international: value & 0x2000 != 0,
+            set_number: value & 0x0FFF
+        }
+    }
+}
+
+impl TryFrom<LinkageInfo> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: LinkageInfo) -> Result<Self, Self::Error> {
+        Ok(MessageElement::new(element_types::LINKAGE_INFO, &value.as_u16()?.to_be_bytes()))
+    }
+}
+
+impl TryFrom<&MessageElement> for LinkageInfo {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::LINKAGE_INFO)?;
+        Ok(LinkageInfo::from_u16(u16::from_be_bytes([data[0], data[1]])))
+    }
+}
+
+// Builds one LINKAGE_INFO element per programme service (this station and its EON services)
+// so they all share the same linkage set, with LA set while the shared programming is on air
+pub fn link_services(set: LinkageInfo, linked: bool, dataset_number: u8, program_service_numbers: &[u8]) -> Result<Vec<MessageElement>, EncodeError> {
+    let info = LinkageInfo { linkage_actuator: linked, ..set };
+
+    program_service_numbers.iter().map(|psn| {
+        let mut element = MessageElement::try_from(info)?;
+        element.set_dsn_psn(dataset_number, *psn);
+        Ok(element)
+    }).collect()
+}
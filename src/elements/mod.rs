@@ -0,0 +1,195 @@
+use core::convert::TryFrom;
+use crate::defs::{ PTY, LengthType, MessageElementType, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+
+pub mod dab;
+pub mod encoder_config;
+pub mod ews;
+pub mod flags;
+pub mod free_format;
+pub mod group_sequence;
+pub mod in_house;
+pub mod linkage;
+pub mod pin;
+pub mod pty;
+pub mod radiotext;
+pub mod signal;
+pub mod slow_labeling;
+pub mod specific;
+pub mod tags;
+pub mod tdc;
+
+// Returns the element data after checking that it holds the expected element type
+// and, for fixed-length types, that the data has the registered length
+pub(crate) fn expect_element(element: &MessageElement, element_type: MessageElementType) -> Result<&[u8], DecodeError> {
+    if element.element_type.code != element_type.code {
+        return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+    }
+
+    let actual = element.data.len();
+    let expected = match element_type.length_type {
+        LengthType::FixedLength(length) => length,
+        LengthType::RangeLength(min, max) => actual.clamp(min, max),
+        LengthType::VariableLength => actual
+    };
+    if actual != expected {
+        return Err(DecodeError::ElementLengthMismatch { mec: element_type.code, expected, actual });
+    }
+
+    Ok(&element.data)
+}
+
+// Decoded form of the elements that have a typed representation.
+// Everything else is kept as Raw.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypedElement {
+    Pty(PTY),
+    DecoderIdentification(flags::DecoderIdentification),
+    MusicSpeech(flags::MusicSpeech),
+    TrafficFlags(flags::TrafficFlags),
+    Pin(pin::Pin),
+    SlowLabeling(slow_labeling::SlowLabeling),
+    LinkageInfo(linkage::LinkageInfo),
+    Ews(ews::EwsMessage),
+    InHouse(in_house::InHouse),
+    FreeFormat(free_format::FreeFormat),
+    TransparentData(tdc::TransparentData),
+    GroupSequence(group_sequence::GroupSequence),
+    ExtendedGroupSequence(group_sequence::GroupSequence),
+    VariantCodeSequence(group_sequence::VariantCodeSequence),
+    SiteAddress(encoder_config::SiteAddressCommand),
+    EncoderAddress(encoder_config::EncoderAddressCommand),
+    CommMode(encoder_config::CommMode),
+    CommPortMode(encoder_config::CommPortMode),
+    CommPortSpeed(encoder_config::CommPortSpeed),
+    CommPortTimeout(encoder_config::CommPortTimeout),
+    EncoderAccessRight(encoder_config::EncoderAccessRight),
+    RdsOnOff(signal::RdsOnOff),
+    RdsPhase(signal::RdsPhase),
+    RdsLevel(signal::RdsLevel),
+    ReferenceInput(signal::ReferenceInput),
+    RadioText(radiotext::RadioText),
+    DynamicLabel(dab::DynamicLabel),
+    DlPlusTags(dab::DlPlusTags),
+    SpecificCommand(specific::SpecificCommandElement),
+    Raw(MessageElement)
+}
+
+impl TypedElement {
+    // Decodes the element value only, see TypedMessageElement to keep its DSN and PSN.
+    // SPECIFIC_COMMAND elements are decoded through the registry when one is given,
+    // and kept as raw vendor payloads otherwise
+    pub fn decode(element: &MessageElement, registry: Option<&specific::SpecificCommandRegistry>) -> Result<Self, DecodeError> {
+        use crate::elements::TypedElement as T;
+
+        let code = element.element_type.code;
+        Ok(match code {
+            c if c == element_types::PTY.code => T::Pty(PTY::try_from(element)?),
+            c if c == element_types::DI.code => T::DecoderIdentification(TryFrom::try_from(element)?),
+            c if c == element_types::MS.code => T::MusicSpeech(TryFrom::try_from(element)?),
+            c if c == element_types::TA_TP.code => T::TrafficFlags(TryFrom::try_from(element)?),
+            c if c == element_types::PIN.code => T::Pin(TryFrom::try_from(element)?),
+            c if c == element_types::SLOW_LABELING.code => T::SlowLabeling(TryFrom::try_from(element)?),
+            c if c == element_types::LINKAGE_INFO.code => T::LinkageInfo(TryFrom::try_from(element)?),
+            c if c == element_types::EWS.code => T::Ews(TryFrom::try_from(element)?),
+            c if c == element_types::IH.code => T::InHouse(TryFrom::try_from(element)?),
+            c if c == element_types::FREE_FORMAT.code => T::FreeFormat(TryFrom::try_from(element)?),
+            c if c == element_types::TDC.code => T::TransparentData(TryFrom::try_from(element)?),
+            c if c == element_types::GROUP_SEQUENCE.code => T::GroupSequence(TryFrom::try_from(element)?),
+            c if c == element_types::EXTENDED_GROUP_SEQUENCE.code => T::ExtendedGroupSequence(TryFrom::try_from(element)?),
+            c if c == element_types::GROUP_VARIANT_CODE_SEQUENCE.code => T::VariantCodeSequence(TryFrom::try_from(element)?),
+            c if c == element_types::SITE_ADDRESS.code => T::SiteAddress(TryFrom::try_from(element)?),
+            c if c == element_types::ENCODER_ADDRESS.code => T::EncoderAddress(TryFrom::try_from(element)?),
+            c if c == element_types::COMM_MODE.code => T::CommMode(TryFrom::try_from(element)?),
+            c if c == element_types::COMM_PORT_MODE.code => T::CommPortMode(TryFrom::try_from(element)?),
+            c if c == element_types::COMM_PORT_SPEED.code => T::CommPortSpeed(TryFrom::try_from(element)?),
+            c if c == element_types::COMM_PORT_TIMEOUT.code => T::CommPortTimeout(TryFrom::try_from(element)?),
+            c if c == element_types::ENCODER_ACCESS_RIGHT.code => T::EncoderAccessRight(TryFrom::try_from(element)?),
+            c if c == element_types::RDS_ON_OFF.code => T::RdsOnOff(TryFrom::try_from(element)?),
+            c if c == element_types::RDS_PHASE.code => T::RdsPhase(TryFrom::try_from(element)?),
+            c if c == element_types::RDS_LEVEL.code => T::RdsLevel(TryFrom::try_from(element)?),
+            c if c == element_types::REFERENCE_INPUT_SELECT.code => T::ReferenceInput(TryFrom::try_from(element)?),
+            c if c == element_types::RT.code => T::RadioText(TryFrom::try_from(element)?),
+            c if c == element_types::DAB_DL_MESSAGE.code => T::DynamicLabel(TryFrom::try_from(element)?),
+            c if c == element_types::DAB_DL_COMMAND.code => T::DlPlusTags(TryFrom::try_from(element)?),
+            c if c == element_types::SPECIFIC_COMMAND.code => {
+                let empty = specific::SpecificCommandRegistry::new();
+                T::SpecificCommand(registry.unwrap_or(&empty).decode(element)?)
+            },
+            _ => T::Raw(element.clone())
+        })
+    }
+
+    // Encodes for DSN 0 and PSN 0 (Raw elements keep their own)
+    pub fn into_element(&self) -> Result<MessageElement, EncodeError> {
+        use crate::elements::TypedElement as T;
+
+        Ok(match self {
+            T::Pty(x) => (*x).into(),
+            T::DecoderIdentification(x) => (*x).into(),
+            T::MusicSpeech(x) => (*x).into(),
+            T::TrafficFlags(x) => (*x).into(),
+            T::Pin(x) => (*x).into(),
+            T::SlowLabeling(x) => MessageElement::try_from(*x)?,
+            T::LinkageInfo(x) => MessageElement::try_from(*x)?,
+            T::Ews(x) => MessageElement::try_from(*x)?,
+            T::InHouse(x) => MessageElement::try_from(*x)?,
+            T::FreeFormat(x) => MessageElement::try_from(*x)?,
+            T::TransparentData(x) => x.into_element()?,
+            T::GroupSequence(x) => x.into_element()?,
+            T::ExtendedGroupSequence(x) => x.into_extended_element()?,
+            T::VariantCodeSequence(x) => MessageElement::try_from(x)?,
+            T::SiteAddress(x) => MessageElement::try_from(*x)?,
+            T::EncoderAddress(x) => MessageElement::try_from(*x)?,
+            T::CommMode(x) => (*x).into(),
+            T::CommPortMode(x) => (*x).into(),
+            T::CommPortSpeed(x) => (*x).into(),
+            T::CommPortTimeout(x) => (*x).into(),
+            T::EncoderAccessRight(x) => MessageElement::try_from(*x)?,
+            T::RdsOnOff(x) => (*x).into(),
+            T::RdsPhase(x) => (*x).into(),
+            T::RdsLevel(x) => (*x).into(),
+            T::ReferenceInput(x) => (*x).into(),
+            T::RadioText(x) => MessageElement::try_from(x)?,
+            T::DynamicLabel(x) => MessageElement::try_from(x)?,
+            T::DlPlusTags(x) => MessageElement::try_from(x)?,
+            T::SpecificCommand(x) => MessageElement::try_from(x)?,
+            T::Raw(x) => x.clone()
+        })
+    }
+}
+
+// Typed element together with the data set and programme service it applies to,
+// so that a decoded command re-encodes for the same service (e.g. an EON PTY)
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypedMessageElement {
+    pub dataset_number: u8,
+    pub program_service_number: u8,
+    pub value: TypedElement
+}
+
+impl TypedMessageElement {
+    pub fn new(value: TypedElement) -> Self {
+        TypedMessageElement {
+            dataset_number: 0,
+            program_service_number: 0,
+            value
+        }
+    }
+
+    pub fn decode(element: &MessageElement, registry: Option<&specific::SpecificCommandRegistry>) -> Result<Self, DecodeError> {
+        Ok(TypedMessageElement {
+            dataset_number: element.dataset_number,
+            program_service_number: element.program_service_number,
+            value: TypedElement::decode(element, registry)?
+        })
+    }
+
+    pub fn into_element(&self) -> Result<MessageElement, EncodeError> {
+        let mut element = self.value.into_element()?;
+        element.set_dsn_psn(self.dataset_number, self.program_service_number);
+        Ok(element)
+    }
+}
@@ -0,0 +1,132 @@
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::time::{ SystemTime, UNIX_EPOCH };
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Programme Item Number: scheduled start of the programme item.
+// Day 0 means no valid PIN (Pin::NONE), which is also how a PIN is cleared.
+// EON services get their own PIN by targeting their PSN with MessageElement::set_dsn_psn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "PinFields"))]
+pub struct Pin {
+    day: u8,
+    hour: u8,
+    minute: u8
+}
+
+// Deserialized fields, checked by Pin::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PinFields {
+    day: u8,
+    hour: u8,
+    minute: u8
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PinFields> for Pin {
+    type Error = EncodeError;
+
+    fn try_from(value: PinFields) -> Result<Self, Self::Error> {
+        if value.day == 0 && value.hour == 0 && value.minute == 0 {
+            return Ok(Pin::NONE);
+        }
+
+        Pin::new(value.day, value.hour, value.minute)
+    }
+}
+
+impl Pin {
+    pub const NONE: Pin = Pin { day: 0, hour: 0, minute: 0 };
+
+    pub fn new(day: u8, hour: u8, minute: u8) -> Result<Self, EncodeError> {
+        if !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(Pin { day, hour, minute })
+    }
+
+    // utc_offset is the local time offset in minutes, as the PIN is published in local time
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: SystemTime, utc_offset: i32) -> Result<Self, EncodeError> {
+        let seconds = time.duration_since(UNIX_EPOCH)
+            .map_err(|_| EncodeError::ParameterOutOfRange)?
+            .as_secs() as i64;
+        let local_minutes = seconds / 60 + utc_offset as i64;
+
+        let days = local_minutes.div_euclid(24 * 60);
+        let minute_of_day = local_minutes.rem_euclid(24 * 60);
+
+        Pin::new(
+            day_of_month(days),
+            (minute_of_day / 60) as u8,
+            (minute_of_day % 60) as u8
+        )
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.day == 0
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    // Layout: day (5 bits), hour (5 bits), minute (6 bits)
+    pub fn as_u16(&self) -> u16 {
+        ((self.day as u16) << 11) | ((self.hour as u16) << 6) | self.minute as u16
+    }
+
+    // Day 0 decodes as Pin::NONE, whatever the hour and minute bits hold
+    pub fn from_u16(value: u16) -> Result<Self, DecodeError> {
+        if value >> 11 == 0 {
+            return Ok(Pin::NONE);
+        }
+
+        Pin::new(
+            (value >> 11) as u8,
+            ((value >> 6) & 0x1F) as u8,
+            (value & 0x3F) as u8
+        ).map_err(|_| DecodeError::InvalidElementData(element_types::PIN.code))
+    }
+}
+
+impl From<Pin> for MessageElement {
+    fn from(value: Pin) -> Self {
+        MessageElement::new(element_types::PIN, &value.as_u16().to_be_bytes())
+    }
+}
+
+impl TryFrom<&MessageElement> for Pin {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::PIN)?;
+        Pin::from_u16(u16::from_be_bytes([data[0], data[1]]))
+    }
+}
+
+// Day of month from a number of days since 1970-01-01
+// (see http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+#[cfg(feature = "std")]
+fn day_of_month(days: i64) -> u8 {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    (day_of_year - (153 * month_index + 2) / 5 + 1) as u8
+}
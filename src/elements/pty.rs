@@ -0,0 +1,20 @@
+use core::convert::TryFrom;
+use num_traits::FromPrimitive;
+use crate::defs::{ PTY, DecodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+impl From<PTY> for MessageElement {
+    fn from(value: PTY) -> Self {
+        MessageElement::new(element_types::PTY, &[value.as_u8()])
+    }
+}
+
+impl TryFrom<&MessageElement> for PTY {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::PTY)?;
+        PTY::from_u8(data[0]).ok_or(DecodeError::InvalidElementData(element_types::PTY.code))
+    }
+}
@@ -0,0 +1,187 @@
+use core::convert::TryFrom;
+use alloc::{ string::{ String, ToString }, vec, vec::Vec };
+use crate::defs::{ GroupType, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::ebulatin::{ to_e1, from_e1 };
+use crate::elements::free_format::FreeFormat;
+use crate::elements::tags::{ ContentType, Tag };
+
+pub const MAX_RADIOTEXT_LENGTH: usize = 64;
+
+// RadioText, preceded in the element by a configuration byte:
+// number of transmissions (b4-b1, 0 for infinite) and A/B flag (b0)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "RadioTextFields", try_from = "RadioTextFields")
+)]
+pub struct RadioText {
+    pub text: String,
+    pub ab_flag: bool,
+    pub transmissions: u8
+}
+
+// Serialized form, with the text both decoded and as E.1 bytes.
+// When deserializing, the raw bytes win over the text if both are given,
+// and both are checked like RadioText::new.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RadioTextFields {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    raw: Option<Vec<u8>>,
+    #[serde(default)]
+    ab_flag: bool,
+    #[serde(default)]
+    transmissions: u8
+}
+
+#[cfg(feature = "serde")]
+impl From<RadioText> for RadioTextFields {
+    fn from(value: RadioText) -> Self {
+        RadioTextFields {
+            raw: Some(to_e1(&value.text)),
+            text: value.text,
+            ab_flag: value.ab_flag,
+            transmissions: value.transmissions
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RadioTextFields> for RadioText {
+    type Error = EncodeError;
+
+    fn try_from(value: RadioTextFields) -> Result<Self, Self::Error> {
+        if value.transmissions > 0x0F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let text = match value.raw {
+            Some(raw) if raw.len() > MAX_RADIOTEXT_LENGTH => return Err(EncodeError::TextTooLong),
+            Some(raw) => from_e1(&raw),
+            None => value.text
+        };
+
+        let mut radiotext = RadioText::new(&text, value.ab_flag)?;
+        radiotext.transmissions = value.transmissions;
+        Ok(radiotext)
+    }
+}
+
+impl RadioText {
+    pub fn new(text: &str, ab_flag: bool) -> Result<Self, EncodeError> {
+        if to_e1(text).len() > MAX_RADIOTEXT_LENGTH {
+            return Err(EncodeError::TextTooLong);
+        }
+
+        Ok(RadioText { text: text.to_string(), ab_flag, transmissions: 0 })
+    }
+}
+
+impl TryFrom<&RadioText> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: &RadioText) -> Result<Self, Self::Error> {
+        if value.transmissions > 0x0F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let text = to_e1(&value.text);
+        if text.len() > MAX_RADIOTEXT_LENGTH {
+            return Err(EncodeError::TextTooLong);
+        }
+
+        let mut data: Vec<u8> = vec![(value.transmissions << 1) | value.ab_flag as u8];
+        data.extend(text);
+        Ok(MessageElement::new(element_types::RT, &data))
+    }
+}
+
+impl TryFrom<&MessageElement> for RadioText {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        if element.element_type.code != element_types::RT.code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        let (config, text) = element.data.split_first()
+            .ok_or(DecodeError::InvalidElementData(element_types::RT.code))?;
+
+        Ok(RadioText {
+            text: from_e1(text),
+            ab_flag: config & 0x01 != 0,
+            transmissions: (config >> 1) & 0x0F
+        })
+    }
+}
+
+// RT+ tags, carried in groups of the RT+ ODA group type (usually 11A).
+// Block 2 holds the item toggle and running bits; two tags span blocks 2 to 4.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RtPlusTags {
+    pub item_toggle: bool,
+    pub item_running: bool,
+    pub tags: Vec<Tag>
+}
+
+impl RtPlusTags {
+    pub fn new(item_toggle: bool, item_running: bool, tags: &[Tag]) -> Result<Self, EncodeError> {
+        if tags.len() > 2 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(RtPlusTags { item_toggle, item_running, tags: tags.to_vec() })
+    }
+
+    pub fn to_free_format(&self, group_type: GroupType) -> Result<FreeFormat, EncodeError> {
+        if self.tags.len() > 2 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        let (type_1, start_1, length_1) = Self::tag_fields(self.tags.first(), 0x3F)?;
+        let (type_2, start_2, length_2) = Self::tag_fields(self.tags.get(1), 0x1F)?;
+
+        FreeFormat::new(
+            group_type,
+            (self.item_toggle as u8) << 4 | (self.item_running as u8) << 3 | type_1 >> 3,
+            ((type_1 & 0x07) as u16) << 13 | (start_1 as u16) << 7 | (length_1 as u16) << 1 | (type_2 >> 5) as u16,
+            ((type_2 & 0x1F) as u16) << 11 | (start_2 as u16) << 5 | length_2 as u16
+        )
+    }
+
+    pub fn from_free_format(value: &FreeFormat) -> Self {
+        let type_1 = (value.block_2 & 0x07) << 3 | (value.block_3 >> 13) as u8;
+        let type_2 = ((value.block_3 & 0x01) << 5) as u8 | (value.block_4 >> 11) as u8;
+
+        let tags = vec![
+            Tag { content_type: ContentType(type_1), start: ((value.block_3 >> 7) & 0x3F) as u8, length: ((value.block_3 >> 1) & 0x3F) as u8 + 1 },
+            Tag { content_type: ContentType(type_2), start: ((value.block_4 >> 5) & 0x3F) as u8, length: (value.block_4 & 0x1F) as u8 + 1 }
+        ];
+
+        RtPlusTags {
+            item_toggle: value.block_2 & 0x10 != 0,
+            item_running: value.block_2 & 0x08 != 0,
+            tags: tags.into_iter().filter(|x| x.content_type != ContentType::DUMMY).collect()
+        }
+    }
+
+    // Content type, start marker and length marker (length minus one) of a tag
+    fn tag_fields(tag: Option<&Tag>, max_length_marker: u8) -> Result<(u8, u8, u8), EncodeError> {
+        match tag {
+            None => Ok((ContentType::DUMMY.0, 0, 0)),
+            Some(tag) => {
+                let length_marker = tag.length.saturating_sub(1);
+                if tag.content_type.0 > 0x3F || tag.start > 0x3F || length_marker > max_length_marker {
+                    return Err(EncodeError::ParameterOutOfRange);
+                }
+
+                Ok((tag.content_type.0, tag.start, length_marker))
+            }
+        }
+    }
+}
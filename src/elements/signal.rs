@@ -0,0 +1,275 @@
+use core::convert::TryFrom;
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Elements addressing a reference input carry its number in bits 15-13
+const MAX_REFERENCE: u8 = 7;
+
+// RDS output on/off, one bit per reference input (b0 for reference 0)
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RdsOnOff(pub u8);
+
+impl RdsOnOff {
+    pub fn is_on(self, reference: u8) -> bool {
+        reference <= MAX_REFERENCE && self.0 & (1 << reference) != 0
+    }
+
+    pub fn set(&mut self, reference: u8, on: bool) -> Result<(), EncodeError> {
+        if reference > MAX_REFERENCE {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        if on {
+            self.0 |= 1 << reference;
+        } else {
+            self.0 &= !(1 << reference);
+        }
+
+        Ok(())
+    }
+}
+
+impl From<RdsOnOff> for MessageElement {
+    fn from(value: RdsOnOff) -> Self {
+        MessageElement::new(element_types::RDS_ON_OFF, &[value.0])
+    }
+}
+
+impl TryFrom<&MessageElement> for RdsOnOff {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::RDS_ON_OFF)?;
+        Ok(RdsOnOff(data[0]))
+    }
+}
+
+// Phase of the RDS subcarrier relative to the 19 kHz pilot of a reference input.
+// Layout: reference (b15-b13), reserved (b12), phase in 0.1° steps from 0 to 3599 (b11-b0).
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "RdsPhaseFields"))]
+pub struct RdsPhase {
+    reference: u8,
+    tenths_of_degree: u16
+}
+
+// Deserialized fields, checked by RdsPhase::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RdsPhaseFields {
+    reference: u8,
+    tenths_of_degree: u16
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RdsPhaseFields> for RdsPhase {
+    type Error = EncodeError;
+
+    fn try_from(value: RdsPhaseFields) -> Result<Self, Self::Error> {
+        RdsPhase::new(value.reference, value.tenths_of_degree)
+    }
+}
+
+impl RdsPhase {
+    pub fn new(reference: u8, tenths_of_degree: u16) -> Result<Self, EncodeError> {
+        if reference > MAX_REFERENCE || tenths_of_degree > 3599 {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(RdsPhase { reference, tenths_of_degree })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_degrees(reference: u8, degrees: f64) -> Result<Self, EncodeError> {
+        let tenths = (degrees.rem_euclid(360.0) * 10.0).round() as u16 % 3600;
+        RdsPhase::new(reference, tenths)
+    }
+
+    pub fn reference(&self) -> u8 {
+        self.reference
+    }
+
+    pub fn degrees(&self) -> f64 {
+        self.tenths_of_degree as f64 / 10.0
+    }
+
+    pub fn as_u16(&self) -> u16 {
+        ((self.reference as u16) << 13) | self.tenths_of_degree
+    }
+}
+
+impl From<RdsPhase> for MessageElement {
+    fn from(value: RdsPhase) -> Self {
+        MessageElement::new(element_types::RDS_PHASE, &value.as_u16().to_be_bytes())
+    }
+}
+
+impl TryFrom<&MessageElement> for RdsPhase {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::RDS_PHASE)?;
+        let value = u16::from_be_bytes([data[0], data[1]]);
+
+        RdsPhase::new((value >> 13) as u8, value & 0x0FFF)
+            .map_err(|_| DecodeError::InvalidElementData(element_types::RDS_PHASE.code))
+    }
+}
+
+// RDS output level (peak-to-peak) for a reference input.
+// Layout: reference (b15-b13), level in mV from 0 to 8191 (b12-b0).
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "RdsLevelFields"))]
+pub struct RdsLevel {
+    reference: u8,
+    millivolts: u16
+}
+
+// Deserialized fields, checked by RdsLevel::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RdsLevelFields {
+    reference: u8,
+    millivolts: u16
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RdsLevelFields> for RdsLevel {
+    type Error = EncodeError;
+
+    fn try_from(value: RdsLevelFields) -> Result<Self, Self::Error> {
+        RdsLevel::new(value.reference, value.millivolts)
+    }
+}
+
+impl RdsLevel {
+    pub fn new(reference: u8, millivolts: u16) -> Result<Self, EncodeError> {
+        if reference > MAX_REFERENCE || millivolts > 0x1FFF {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(RdsLevel { reference, millivolts })
+    }
+
+    pub fn reference(&self) -> u8 {
+        self.reference
+    }
+
+    pub fn millivolts(&self) -> u16 {
+        self.millivolts
+    }
+
+    pub fn as_u16(&self) -> u16 {
+        ((self.reference as u16) << 13) | self.millivolts
+    }
+}
+
+impl From<RdsLevel> for MessageElement {
+    fn from(value: RdsLevel) -> Self {
+        MessageElement::new(element_types::RDS_LEVEL, &value.as_u16().to_be_bytes())
+    }
+}
+
+impl TryFrom<&MessageElement> for RdsLevel {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::RDS_LEVEL)?;
+        let value = u16::from_be_bytes([data[0], data[1]]);
+        Ok(RdsLevel { reference: (value >> 13) as u8, millivolts: value & 0x1FFF })
+    }
+}
+
+// Reference input the encoder locks on; reference 0 is the internal clock
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "u8"))]
+pub struct ReferenceInput(u8);
+
+impl TryFrom<u8> for ReferenceInput {
+    type Error = EncodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        ReferenceInput::new(value)
+    }
+}
+
+impl ReferenceInput {
+    pub fn new(reference: u8) -> Result<Self, EncodeError> {
+        if reference > MAX_REFERENCE {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(ReferenceInput(reference))
+    }
+
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+}
+
+impl From<ReferenceInput> for MessageElement {
+    fn from(value: ReferenceInput) -> Self {
+        MessageElement::new(element_types::REFERENCE_INPUT_SELECT, &[value.0])
+    }
+}
+
+impl TryFrom<&MessageElement> for ReferenceInput {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::REFERENCE_INPUT_SELECT)?;
+        ReferenceInput::new(data[0])
+            .map_err(|_| DecodeError::InvalidElementData(element_types::REFERENCE_INPUT_SELECT.code))
+    }
+}
+
+// Signal settings collected from received elements, for whatever generates the RDS subcarrier.
+// Settings not received yet stay at None so the generator can keep its own defaults.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct SignalSettings {
+    pub on_off: Option<RdsOnOff>,
+    pub reference: Option<ReferenceInput>,
+    pub phases: [Option<RdsPhase>; 8],
+    pub levels: [Option<RdsLevel>; 8]
+}
+
+impl SignalSettings {
+    // Returns whether the element was a signal control element
+    pub fn apply(&mut self, element: &MessageElement) -> Result<bool, DecodeError> {
+        let code = element.element_type.code;
+
+        if code == element_types::RDS_ON_OFF.code {
+            self.on_off = Some(RdsOnOff::try_from(element)?);
+        } else if code == element_types::REFERENCE_INPUT_SELECT.code {
+            self.reference = Some(ReferenceInput::try_from(element)?);
+        } else if code == element_types::RDS_PHASE.code {
+            let phase = RdsPhase::try_from(element)?;
+            self.phases[phase.reference as usize] = Some(phase);
+        } else if code == element_types::RDS_LEVEL.code {
+            let level = RdsLevel::try_from(element)?;
+            self.levels[level.reference as usize] = Some(level);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn selected_reference(&self) -> usize {
+        self.reference.map_or(0, |x| x.number() as usize)
+    }
+
+    pub fn output_enabled(&self) -> Option<bool> {
+        self.on_off.map(|x| x.is_on(self.selected_reference() as u8))
+    }
+
+    pub fn phase(&self) -> Option<RdsPhase> {
+        self.phases[self.selected_reference()]
+    }
+
+    pub fn level(&self) -> Option<RdsLevel> {
+        self.levels[self.selected_reference()]
+    }
+}
@@ -0,0 +1,183 @@
+use core::convert::TryFrom;
+use phf::phf_map;
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+use crate::elements::expect_element;
+
+// Slow labelling codes, sent as block 3 of a type 1A group.
+// The LA bit (b15) is not part of this element, see LINKAGE_INFO.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlowLabeling {
+    // Variant 0: 4 bits of paging (OPC) and the Extended Country Code
+    PagingAndEcc { paging: u8, ecc: u8 },
+    // Variant 1
+    TmcIdentification(u16),
+    // Variant 2
+    PagingIdentification(u16),
+    // Variant 3: Language Identification Code
+    LanguageCode(u8),
+    // Variant 6
+    Broadcaster(u16),
+    // Variant 7
+    EwsChannel(u16),
+    // Variants 4 and 5 are not assigned
+    Unassigned { variant: u8, data: u16 }
+}
+
+impl SlowLabeling {
+    pub fn extended_country_code(country: &str, pi: u16) -> Option<Self> {
+        ecc_from_country(country, pi).map(|ecc| SlowLabeling::PagingAndEcc { paging: 0, ecc })
+    }
+
+    pub fn language(iso639: &str) -> Option<Self> {
+        language_code_from_iso639(iso639).map(SlowLabeling::LanguageCode)
+    }
+
+    pub fn variant_code(&self) -> u8 {
+        match self {
+            SlowLabeling::PagingAndEcc { .. } => 0,
+            SlowLabeling::TmcIdentification(_) => 1,
+            SlowLabeling::PagingIdentification(_) => 2,
+            SlowLabeling::LanguageCode(_) => 3,
+            SlowLabeling::Broadcaster(_) => 6,
+            SlowLabeling::EwsChannel(_) => 7,
+            SlowLabeling::Unassigned { variant, .. } => *variant
+        }
+    }
+
+    pub fn to_u16(&self) -> Result<u16, EncodeError> {
+        let data: u16 = match *self {
+            SlowLabeling::PagingAndEcc { paging, ecc } => {
+                if paging > 0x0F {
+                    return Err(EncodeError::ParameterOutOfRange);
+                }
+                ((paging as u16) << 8) | ecc as u16
+            },
+            SlowLabeling::LanguageCode(code) => code as u16,
+            SlowLabeling::Unassigned { variant, data } => {
+                if variant != 4 && variant != 5 {
+                    return Err(EncodeError::ParameterOutOfRange);
+                }
+                data
+            },
+            SlowLabeling::TmcIdentification(data)
+            | SlowLabeling::PagingIdentification(data)
+            | SlowLabeling::Broadcaster(data)
+            | SlowLabeling::EwsChannel(data) => data
+        };
+
+        if data > 0x0FFF {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(((self.variant_code() as u16) << 12) | data)
+    }
+
+    pub fn from_u16(value: u16) -> Self {
+        let variant = ((value >> 12) & 0x07) as u8;
+        let data = value & 0x0FFF;
+
+        match variant {
+            0 => SlowLabeling::PagingAndEcc { paging: (data >> 8) as u8, ecc: (data & 0xFF) as u8 },
+            1 => SlowLabeling::TmcIdentification(data),
+            2 => SlowLabeling::PagingIdentification(data),
+            3 => SlowLabeling::LanguageCode((data & 0xFF) as u8),
+            6 => SlowLabeling::Broadcaster(data),
+            7 => SlowLabeling::EwsChannel(data),
+            _ => SlowLabeling::Unassigned { variant, data }
+        }
+    }
+}
+
+impl TryFrom<SlowLabeling> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: SlowLabeling) -> Result<Self, Self::Error> {
+        Ok(MessageElement::new(element_types::SLOW_LABELING, &value.to_u16()?.to_be_bytes()))
+    }
+}
+
+impl TryFrom<&MessageElement> for SlowLabeling {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        let data = expect_element(element, element_types::SLOW_LABELING)?;
+        Ok(SlowLabeling::from_u16(u16::from_be_bytes([data[0], data[1]])))
+    }
+}
+
+// Extended Country Codes (IEC 62106 Annex D), keyed by ISO 3166-1 alpha-2 code.
+// Each entry holds the ECC and the PI country nibbles allocated to that country.
+static ECC: phf::Map<&'static str, (u8, &'static [u8])> = phf_map! {
+    // ECC E0
+    "DE" => (0xE0, &[0x1, 0xD]), "DZ" => (0xE0, &[0x2]), "AD" => (0xE0, &[0x3]), "IL" => (0xE0, &[0x4]),
+    "IT" => (0xE0, &[0x5]), "BE" => (0xE0, &[0x6]), "RU" => (0xE0, &[0x7]), "PS" => (0xE0, &[0x8]),
+    "AL" => (0xE0, &[0x9]), "AT" => (0xE0, &[0xA]), "HU" => (0xE0, &[0xB]), "MT" => (0xE0, &[0xC]),
+    "EG" => (0xE0, &[0xF]),
+
+    // ECC E1
+    "GR" => (0xE1, &[0x1]), "CY" => (0xE1, &[0x2]), "SM" => (0xE1, &[0x3]), "CH" => (0xE1, &[0x4]),
+    "JO" => (0xE1, &[0x5]), "FI" => (0xE1, &[0x6]), "LU" => (0xE1, &[0x7]), "BG" => (0xE1, &[0x8]),
+    "DK" => (0xE1, &[0x9]), "GI" => (0xE1, &[0xA]), "IQ" => (0xE1, &[0xB]), "GB" => (0xE1, &[0xC]),
+    "LY" => (0xE1, &[0xD]), "RO" => (0xE1, &[0xE]), "FR" => (0xE1, &[0xF]),
+
+    // ECC E2
+    "MA" => (0xE2, &[0x1]), "CZ" => (0xE2, &[0x2]), "PL" => (0xE2, &[0x3]), "VA" => (0xE2, &[0x4]),
+    "SK" => (0xE2, &[0x5]), "SY" => (0xE2, &[0x6]), "TN" => (0xE2, &[0x7]), "LI" => (0xE2, &[0x9]),
+    "IS" => (0xE2, &[0xA]), "MC" => (0xE2, &[0xB]), "LT" => (0xE2, &[0xC]), "RS" => (0xE2, &[0xD]),
+    "ES" => (0xE2, &[0xE]), "NO" => (0xE2, &[0xF]),
+
+    // ECC E3
+    "ME" => (0xE3, &[0x1]), "IE" => (0xE3, &[0x2]), "TR" => (0xE3, &[0x3]), "MK" => (0xE3, &[0x4]),
+    "NL" => (0xE3, &[0x8]), "LV" => (0xE3, &[0x9]), "LB" => (0xE3, &[0xA]), "AZ" => (0xE3, &[0xB]),
+    "HR" => (0xE3, &[0xC]), "KZ" => (0xE3, &[0xD]), "SE" => (0xE3, &[0xE]), "BY" => (0xE3, &[0xF]),
+
+    // ECC E4
+    "MD" => (0xE4, &[0x1]), "EE" => (0xE4, &[0x2]), "KG" => (0xE4, &[0x3]), "UA" => (0xE4, &[0x6]),
+    "XK" => (0xE4, &[0x7]), "PT" => (0xE4, &[0x8]), "SI" => (0xE4, &[0x9]), "AM" => (0xE4, &[0xA]),
+    "UZ" => (0xE4, &[0xB]), "GE" => (0xE4, &[0xC]), "TM" => (0xE4, &[0xE]), "BA" => (0xE4, &[0xF]),
+
+    // North America
+    "US" => (0xA0, &[0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xD, 0xE]),
+    "CA" => (0xA1, &[0xB, 0xC, 0xD, 0xE]),
+    "MX" => (0xA5, &[0xB, 0xD, 0xE, 0xF])
+};
+
+// RDS Language Identification Codes (IEC 62106 Annex J), keyed by ISO 639-1 code
+static LANGUAGE_CODES: phf::Map<&'static str, u8> = phf_map! {
+    "sq" => 0x01, "br" => 0x02, "ca" => 0x03, "hr" => 0x04, "cy" => 0x05, "cs" => 0x06, "da" => 0x07,
+    "de" => 0x08, "en" => 0x09, "es" => 0x0A, "eo" => 0x0B, "et" => 0x0C, "eu" => 0x0D, "fo" => 0x0E,
+    "fr" => 0x0F, "fy" => 0x10, "ga" => 0x11, "gd" => 0x12, "gl" => 0x13, "is" => 0x14, "it" => 0x15,
+    "se" => 0x16, "la" => 0x17, "lv" => 0x18, "lb" => 0x19, "lt" => 0x1A, "hu" => 0x1B, "mt" => 0x1C,
+    "nl" => 0x1D, "no" => 0x1E, "oc" => 0x1F, "pl" => 0x20, "pt" => 0x21, "ro" => 0x22, "rm" => 0x23,
+    "sr" => 0x24, "sk" => 0x25, "sl" => 0x26, "fi" => 0x27, "sv" => 0x28, "tr" => 0x29, "wa" => 0x2B,
+
+    "zu" => 0x45, "vi" => 0x46, "uz" => 0x47, "ur" => 0x48, "uk" => 0x49, "th" => 0x4A, "te" => 0x4B,
+    "tt" => 0x4C, "ta" => 0x4D, "tg" => 0x4E, "sw" => 0x4F, "so" => 0x51, "si" => 0x52, "sn" => 0x53,
+    "ru" => 0x56, "qu" => 0x57, "ps" => 0x58, "pa" => 0x59, "fa" => 0x5A, "or" => 0x5C, "ne" => 0x5D,
+    "nd" => 0x5E, "mr" => 0x5F, "ms" => 0x61, "mg" => 0x62, "mk" => 0x63, "lo" => 0x64, "ko" => 0x65,
+    "km" => 0x66, "kk" => 0x67, "kn" => 0x68, "ja" => 0x69, "id" => 0x6A, "hi" => 0x6B, "he" => 0x6C,
+    "ha" => 0x6D, "gn" => 0x6E, "gu" => 0x6F, "el" => 0x70, "ka" => 0x71, "ff" => 0x72, "cv" => 0x74,
+    "zh" => 0x75, "my" => 0x76, "bg" => 0x77, "bn" => 0x78, "be" => 0x79, "bm" => 0x7A, "az" => 0x7B,
+    "as" => 0x7C, "hy" => 0x7D, "ar" => 0x7E, "am" => 0x7F
+};
+
+// Looks up the ECC for an ISO 3166-1 alpha-2 country code,
+// checking that the PI code's country nibble belongs to that country
+pub fn ecc_from_country(country: &str, pi: u16) -> Option<u8> {
+    let key = country.to_ascii_uppercase();
+    let (ecc, nibbles) = ECC.get(key.as_str())?;
+
+    let pi_nibble = (pi >> 12) as u8;
+    if nibbles.contains(&pi_nibble) {
+        Some(*ecc)
+    } else {
+        None
+    }
+}
+
+pub fn language_code_from_iso639(code: &str) -> Option<u8> {
+    let key = code.to_ascii_lowercase();
+    LANGUAGE_CODES.get(key.as_str()).copied()
+}
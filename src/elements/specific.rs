@@ -0,0 +1,142 @@
+use core::any::Any;
+use alloc::collections::BTreeMap;
+use core::convert::TryFrom;
+use core::fmt::Debug;
+use alloc::{ boxed::Box, vec::Vec };
+use crate::defs::{ DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+
+// Vendor payload of a SPECIFIC_COMMAND element.
+// The element carries the manufacturer ID (2 bytes) followed by the payload.
+pub trait SpecificCommand: Debug + Send + Sync {
+    fn manufacturer_id(&self) -> u16;
+    fn payload(&self) -> Result<Vec<u8>, EncodeError>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+// Turns the payload of a vendor's SPECIFIC_COMMAND elements back into a typed command
+pub trait SpecificCommandDecoder: Send + Sync {
+    fn manufacturer_id(&self) -> u16;
+    fn decode(&self, payload: &[u8]) -> Result<Box<dyn SpecificCommand>, DecodeError>;
+}
+
+pub fn specific_command_element(command: &dyn SpecificCommand) -> Result<MessageElement, EncodeError> {
+    let payload = command.payload()?;
+    if payload.len() > 252 {
+        return Err(EncodeError::ElementTooLarge);
+    }
+
+    let mut data: Vec<u8> = command.manufacturer_id().to_be_bytes().to_vec();
+    data.extend(payload);
+    Ok(MessageElement::new(element_types::SPECIFIC_COMMAND, &data))
+}
+
+#[derive(Debug)]
+pub enum SpecificCommandElement {
+    Known(Box<dyn SpecificCommand>),
+    // No decoder is registered for this manufacturer
+    Raw { manufacturer_id: u16, payload: Vec<u8> }
+}
+
+impl SpecificCommandElement {
+    pub fn manufacturer_id(&self) -> u16 {
+        match self {
+            SpecificCommandElement::Known(command) => command.manufacturer_id(),
+            SpecificCommandElement::Raw { manufacturer_id, .. } => *manufacturer_id
+        }
+    }
+
+    pub fn downcast_ref<T: SpecificCommand + 'static>(&self) -> Option<&T> {
+        match self {
+            SpecificCommandElement::Known(command) => command.as_any().downcast_ref::<T>(),
+            SpecificCommandElement::Raw { .. } => None
+        }
+    }
+}
+
+impl TryFrom<&SpecificCommandElement> for MessageElement {
+    type Error = EncodeError;
+
+    fn try_from(value: &SpecificCommandElement) -> Result<Self, Self::Error> {
+        match value {
+            SpecificCommandElement::Known(command) => specific_command_element(command.as_ref()),
+            SpecificCommandElement::Raw { manufacturer_id, payload } => {
+                if payload.len() > 252 {
+                    return Err(EncodeError::ElementTooLarge);
+                }
+
+                let mut data: Vec<u8> = manufacturer_id.to_be_bytes().to_vec();
+                data.extend_from_slice(payload);
+                Ok(MessageElement::new(element_types::SPECIFIC_COMMAND, &data))
+            }
+        }
+    }
+}
+
+// Serialized as the manufacturer ID and payload bytes, deserialized as Raw
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SpecificCommandFields {
+    manufacturer_id: u16,
+    payload: Vec<u8>
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpecificCommandElement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let payload = match self {
+            SpecificCommandElement::Known(command) => command.payload().map_err(serde::ser::Error::custom)?,
+            SpecificCommandElement::Raw { payload, .. } => payload.clone()
+        };
+
+        SpecificCommandFields { manufacturer_id: self.manufacturer_id(), payload }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SpecificCommandElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = SpecificCommandFields::deserialize(deserializer)?;
+        Ok(SpecificCommandElement::Raw { manufacturer_id: fields.manufacturer_id, payload: fields.payload })
+    }
+}
+
+// Vendor decoders, keyed by manufacturer ID
+#[derive(Default)]
+pub struct SpecificCommandRegistry {
+    decoders: BTreeMap<u16, Box<dyn SpecificCommandDecoder>>
+}
+
+impl SpecificCommandRegistry {
+    pub fn new() -> Self {
+        SpecificCommandRegistry::default()
+    }
+
+    // Returns the decoder previously registered for the same manufacturer, if any
+    pub fn register(&mut self, decoder: Box<dyn SpecificCommandDecoder>) -> Option<Box<dyn SpecificCommandDecoder>> {
+        self.decoders.insert(decoder.manufacturer_id(), decoder)
+    }
+
+    pub fn is_registered(&self, manufacturer_id: u16) -> bool {
+        self.decoders.contains_key(&manufacturer_id)
+    }
+
+    pub fn decode(&self, element: &MessageElement) -> Result<SpecificCommandElement, DecodeError> {
+        let code = element_types::SPECIFIC_COMMAND.code;
+        if element.element_type.code != code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        if element.data.len() < 2 {
+            return Err(DecodeError::InvalidElementData(code));
+        }
+
+        let manufacturer_id = u16::from_be_bytes([element.data[0], element.data[1]]);
+        let payload = &element.data[2..];
+
+        Ok(match self.decoders.get(&manufacturer_id) {
+            Some(decoder) => SpecificCommandElement::Known(decoder.decode(payload)?),
+            None => SpecificCommandElement::Raw { manufacturer_id, payload: payload.to_vec() }
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use core::convert::TryFrom;
+use alloc::{ string::String, vec, vec::Vec };
+use crate::defs::{ GroupType, EncodeError };
+use crate::protocol::MessageElement;
+use crate::elements::radiotext::{ RadioText, RtPlusTags };
+use crate::elements::dab::{ DabCharset, DynamicLabel, DlPlusTags };
+
+// Content types shared by RT+ and DL Plus (RT+ class codes)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentType(pub u8);
+
+impl ContentType {
+    pub const DUMMY: ContentType = ContentType(0);
+    pub const ITEM_TITLE: ContentType = ContentType(1);
+    pub const ITEM_ALBUM: ContentType = ContentType(2);
+    pub const ITEM_TRACKNUMBER: ContentType = ContentType(3);
+    pub const ITEM_ARTIST: ContentType = ContentType(4);
+    pub const ITEM_COMPOSITION: ContentType = ContentType(5);
+    pub const ITEM_MOVEMENT: ContentType = ContentType(6);
+    pub const ITEM_CONDUCTOR: ContentType = ContentType(7);
+    pub const ITEM_COMPOSER: ContentType = ContentType(8);
+    pub const ITEM_BAND: ContentType = ContentType(9);
+    pub const ITEM_COMMENT: ContentType = ContentType(10);
+    pub const ITEM_GENRE: ContentType = ContentType(11);
+    pub const INFO_NEWS: ContentType = ContentType(12);
+    pub const STATIONNAME_SHORT: ContentType = ContentType(31);
+    pub const STATIONNAME_LONG: ContentType = ContentType(32);
+    pub const PROGRAMME_NOW: ContentType = ContentType(33);
+}
+
+// A tagged part of a text, in characters from the start of the text
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tag {
+    pub content_type: ContentType,
+    pub start: u8,
+    pub length: u8
+}
+
+// Text with RT+/DL Plus tags, rendered both as RDS RadioText and DAB Dynamic Label
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaggedText {
+    pub text: String,
+    pub tags: Vec<Tag>,
+    pub item_toggle: bool,
+    pub item_running: bool
+}
+
+impl TaggedText {
+    pub fn new() -> Self {
+        TaggedText {
+            item_running: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn plain(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    pub fn tagged(mut self, content_type: ContentType, text: &str) -> Self {
+        let start = self.text.chars().count();
+        let length = text.chars().count();
+        self.text.push_str(text);
+
+        if length > 0 && start + length <= 255 {
+            self.tags.push(Tag { content_type, start: start as u8, length: length as u8 });
+        }
+
+        self
+    }
+
+    // RT element, followed by the RT+ tags sent as a free format group of the RT+ ODA group type
+    pub fn radiotext_elements(&self, ab_flag: bool, rt_plus_group: Option<GroupType>) -> Result<Vec<MessageElement>, EncodeError> {
+        let radiotext = RadioText::new(&self.text, ab_flag)?;
+        let mut elements = vec![MessageElement::try_from(&radiotext)?];
+
+        if let Some(group_type) = rt_plus_group {
+            let tags = RtPlusTags::new(self.item_toggle, self.item_running, &self.tags)?;
+            elements.push(MessageElement::try_from(tags.to_free_format(group_type)?)?);
+        }
+
+        Ok(elements)
+    }
+
+    pub fn dynamic_label_elements(&self, charset: DabCharset) -> Result<Vec<MessageElement>, EncodeError> {
+        let label = DynamicLabel::new(&self.text, charset)?;
+        let tags = DlPlusTags::new(self.item_toggle, self.item_running, &self.tags)?;
+
+        Ok(vec![
+            MessageElement::try_from(&label)?,
+            MessageElement::try_from(&tags)?
+        ])
+    }
+}
@@ -0,0 +1,90 @@
+use core::convert::TryFrom;
+use alloc::{ vec, vec::Vec };
+use crate::defs::{ GroupVersion, DecodeError, EncodeError, element_types };
+use crate::protocol::MessageElement;
+
+// Largest element payload that holds a whole number of groups of either version
+const MAX_CHUNK_LENGTH: usize = 252;
+
+// Transparent Data Channel: raw bytes on one of 32 channels (address in block 2),
+// carried 4 bytes per type 5A group or 2 bytes per type 5B group
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransparentData {
+    pub channel: u8,
+    pub data: Vec<u8>
+}
+
+impl TransparentData {
+    pub fn new(channel: u8, data: &[u8]) -> Result<Self, EncodeError> {
+        if channel > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+
+        Ok(TransparentData { channel, data: data.to_vec() })
+    }
+
+    pub fn bytes_per_group(version: GroupVersion) -> usize {
+        match version {
+            GroupVersion::A => 4,
+            GroupVersion::B => 2
+        }
+    }
+
+    // Splits the payload into the contents of each 5A/5B group, padding the last one
+    // with zeroes. The elements carry the payload unpadded; the encoder pads the groups.
+    pub fn groups(&self, version: GroupVersion) -> Vec<Vec<u8>> {
+        let group_length = Self::bytes_per_group(version);
+
+        self.data.chunks(group_length).map(|chunk| {
+            let mut group = chunk.to_vec();
+            group.resize(group_length, 0x00);
+            group
+        }).collect()
+    }
+
+    // Single TDC element, for payloads of up to 252 bytes
+    pub fn into_element(&self) -> Result<MessageElement, EncodeError> {
+        if self.channel > 0x1F {
+            return Err(EncodeError::ParameterOutOfRange);
+        }
+        if self.data.len() > MAX_CHUNK_LENGTH {
+            return Err(EncodeError::ElementTooLarge);
+        }
+
+        let mut data: Vec<u8> = vec![self.channel];
+        data.extend_from_slice(&self.data);
+        Ok(MessageElement::new(element_types::TDC, &data))
+    }
+
+    // Splits the payload over as many TDC elements as needed. Every element but
+    // the last carries whole groups of either version, so the payload decodes back
+    // unchanged whichever group version the encoder sends it with.
+    pub fn into_elements(&self) -> Result<Vec<MessageElement>, EncodeError> {
+        if self.data.is_empty() {
+            return Ok(vec![self.into_element()?]);
+        }
+
+        self.data.chunks(MAX_CHUNK_LENGTH)
+            .map(|chunk| TransparentData { channel: self.channel, data: chunk.to_vec() }.into_element())
+            .collect()
+    }
+}
+
+impl TryFrom<&MessageElement> for TransparentData {
+    type Error = DecodeError;
+
+    fn try_from(element: &MessageElement) -> Result<Self, Self::Error> {
+        if element.element_type.code != element_types::TDC.code {
+            return Err(DecodeError::UnexpectedElementType(element.element_type.code));
+        }
+
+        match element.data.split_first() {
+            Some((channel, data)) if *channel <= 0x1F => Ok(TransparentData {
+                channel: *channel,
+                data: data.to_vec()
+            }),
+            _ => Err(DecodeError::InvalidElementData(element_types::TDC.code))
+        }
+    }
+}
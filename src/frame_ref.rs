@@ -0,0 +1,157 @@
+// Borrowed views of a frame and its elements, decoded without allocating
+use crate::defs::{ LengthType, DSNPSNType, MessageElementType, DecodeError, element_types };
+use crate::framing::{ StuffingMode, Crc16, Unstuffer };
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ElementRef<'a> {
+    pub element_type: MessageElementType,
+    pub dataset_number: u8,
+    pub program_service_number: u8,
+    pub data: &'a [u8]
+}
+
+impl<'a> ElementRef<'a> {
+    // Reads the element at the start of `bytes`, returning it with its encoded length
+    pub fn parse(bytes: &'a [u8]) -> Result<(ElementRef<'a>, usize), DecodeError> {
+        let element_length = MessageElementType::get_next_element_length(bytes)?;
+
+        let element_type = element_types::from_code(&bytes[0])
+            .ok_or(DecodeError::UnknownElementType(bytes[0]))?;
+
+        let (dataset_number, program_service_number, mut header_length) = match element_type.dsn_psn_type {
+            DSNPSNType::None => (0, 0, 1),
+            DSNPSNType::DSNOnly => (*bytes.get(1).ok_or(DecodeError::MessageTooShort)?, 0, 2),
+            DSNPSNType::All => (
+                *bytes.get(1).ok_or(DecodeError::MessageTooShort)?,
+                *bytes.get(2).ok_or(DecodeError::MessageTooShort)?,
+                3
+            )
+        };
+
+        if let LengthType::VariableLength = element_type.length_type {
+            // MEL field, already read by get_next_element_length
+            header_length += 1;
+        }
+
+        if bytes.len() < element_length {
+            return Err(DecodeError::ElementLengthMismatch {
+                mec: element_type.code,
+                expected: element_length - header_length,
+                actual: bytes.len() - header_length
+            });
+        }
+
+        Ok((
+            ElementRef {
+                element_type,
+                dataset_number,
+                program_service_number,
+                data: &bytes[header_length..element_length]
+            },
+            element_length
+        ))
+    }
+}
+
+// Iterates the elements of a message field. Stops after the first error.
+#[derive(Debug, Clone)]
+pub struct ElementIter<'a> {
+    remaining: &'a [u8]
+}
+
+impl<'a> ElementIter<'a> {
+    pub fn new(message: &'a [u8]) -> Self {
+        ElementIter { remaining: message }
+    }
+}
+
+impl<'a> Iterator for ElementIter<'a> {
+    type Item = Result<ElementRef<'a>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+
+        match ElementRef::parse(self.remaining) {
+            Ok((element, length)) => {
+                self.remaining = &self.remaining[length..];
+                Some(Ok(element))
+            },
+            Err(e) => {
+                self.remaining = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrameRef<'a> {
+    pub sequence_counter: u8,
+    pub site_address: u16,
+    pub encoder_address: u8,
+    pub message: &'a [u8]
+}
+
+impl<'a> FrameRef<'a> {
+    // Unstuffs `bytes` (STA to STP) into `scratch` and checks the CRC.
+    // `scratch` needs at most as many bytes as the frame itself.
+    pub fn parse(bytes: &[u8], scratch: &'a mut [u8]) -> Result<FrameRef<'a>, DecodeError> {
+        Self::parse_with(bytes, scratch, StuffingMode::Strict)
+    }
+
+    pub fn parse_with(bytes: &[u8], scratch: &'a mut [u8], stuffing: StuffingMode) -> Result<FrameRef<'a>, DecodeError> {
+        // STA and STP are ignored
+        if bytes.len() < 2 {
+            return Err(DecodeError::FrameHeaderTruncated);
+        }
+
+        // The CRC is updated as bytes are unstuffed, two bytes behind,
+        // so that it covers everything but the CRC field at the end
+        let mut crc = Crc16::new();
+        let mut length: usize = 0;
+        for byte in Unstuffer::new(&bytes[1..bytes.len() - 1], stuffing) {
+            let byte = byte.map_err(|e| match e {
+                // Position in the frame, counting STA
+                DecodeError::BadStuffing { position } => DecodeError::BadStuffing { position: position + 1 },
+                e => e
+            })?;
+
+            *scratch.get_mut(length).ok_or(DecodeError::BufferTooSmall)? = byte;
+            if length >= 2 {
+                crc.update(&scratch[length - 2..length - 1]);
+            }
+            length += 1;
+        }
+        let unstuffed: &'a [u8] = &scratch[..length];
+
+        // ADD, SEQ, MEL and CRC
+        if unstuffed.len() < 6 {
+            return Err(DecodeError::FrameHeaderTruncated);
+        }
+
+        let (crc_data, crc_field) = unstuffed.split_at(unstuffed.len() - 2);
+        if crc.finalize() != u16::from_be_bytes([crc_field[0], crc_field[1]]) {
+            return Err(DecodeError::CRCError);
+        }
+
+        let address = u16::from_be_bytes([crc_data[0], crc_data[1]]);
+        let message = &crc_data[4..];
+        let message_length = crc_data[3] as usize;
+        if message.len() != message_length {
+            return Err(DecodeError::MessageLengthMismatch { declared: message_length, actual: message.len() });
+        }
+
+        Ok(FrameRef {
+            sequence_counter: crc_data[2],
+            site_address: (address & 0xFFC0) >> 6,
+            encoder_address: (address & 0x3F) as u8,
+            message
+        })
+    }
+
+    pub fn elements(&self) -> ElementIter<'a> {
+        ElementIter::new(self.message)
+    }
+}
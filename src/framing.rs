@@ -0,0 +1,275 @@
+// Frame layout helpers that only need core: CRC, byte stuffing and
+// encoding into caller-provided (fixed-capacity) buffers
+use crate::defs::{ LengthType, DSNPSNType, MessageElementType, EncodeError, DecodeError };
+
+// The MEL field of a frame is a single byte
+pub const MAX_MESSAGE_LENGTH: usize = 255;
+
+// MEC, DSN, PSN, MEL and 254 bytes of data
+pub const MAX_ELEMENT_LENGTH: usize = 4 + 254;
+
+// STA, then ADD, SEQ, MEL, message and CRC all stuffed, then STP
+pub const MAX_FRAME_LENGTH: usize = 1 + 2 * (2 + 1 + 1 + MAX_MESSAGE_LENGTH + 2) + 1;
+
+#[cfg(feature = "heapless")]
+pub type FrameBuffer = heapless::Vec<u8, MAX_FRAME_LENGTH>;
+
+// CRC-16/GENIBUS: the CCITT polynomial 0x1021, MSB first, initial value 0xFFFF
+// and inverted output. Computed one byte at a time from a 256-entry table.
+const CRC16_POLYNOMIAL: u16 = 0x1021;
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        // Remainder of the byte on its own, shifted through the polynomial bit by bit
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ CRC16_POLYNOMIAL } else { crc << 1 };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+// Incremental CRC over data that arrives in several parts
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Crc16 {
+    state: u16
+}
+
+impl Crc16 {
+    pub fn new() -> Self {
+        Crc16 { state: 0xFFFF }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for c in data {
+            let index = ((self.state >> 8) as u8 ^ *c) as usize;
+            self.state = (self.state << 8) ^ CRC16_TABLE[index];
+        }
+    }
+
+    pub fn finalize(&self) -> u16 {
+        self.state ^ 0xFFFF
+    }
+}
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn compute_crc16_genibus(data: &[u8]) -> u16 {
+    let mut crc = Crc16::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+pub fn address_field(site_address: u16, encoder_address: u8) -> Result<u16, EncodeError> {
+    if site_address > 1023 {
+        return Err(EncodeError::InvalidSiteAddress);
+    }
+
+    if encoder_address > 63 {
+        return Err(EncodeError::InvalidEncoderAddress);
+    }
+
+    Ok(((site_address & 0x3FF) << 6) | (encoder_address & 0x3F) as u16)
+}
+
+// Returns the number of bytes written to `out`
+pub fn stuff_into(data: &[u8], out: &mut [u8]) -> Result<usize, EncodeError> {
+    let mut written: usize = 0;
+
+    for c in data {
+        let current = *c;
+        let bytes: &[u8] = if current >= 0xFD { &[0xFD, current - 0xFD] } else { &[current] };
+
+        let target = out.get_mut(written..written + bytes.len())
+            .ok_or(EncodeError::BufferTooSmall)?;
+        target.copy_from_slice(bytes);
+        written += bytes.len();
+    }
+
+    Ok(written)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StuffingMode {
+    // 0xFD must be followed by 0, 1 or 2, and 0xFE/0xFF must not appear unstuffed
+    Strict,
+    // For broken encoders: invalid sequences are kept as they are
+    Lenient
+}
+
+// Returns the number of bytes written to `out`
+pub fn unstuff_into(data: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    unstuff_into_with(data, out, StuffingMode::Strict)
+}
+
+pub fn unstuff_into_with(data: &[u8], out: &mut [u8], mode: StuffingMode) -> Result<usize, DecodeError> {
+    let mut written: usize = 0;
+    for byte in Unstuffer::new(data, mode) {
+        *out.get_mut(written).ok_or(DecodeError::BufferTooSmall)? = byte?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+// Unstuffed bytes, one at a time; stops after the first error
+pub(crate) struct Unstuffer<'a> {
+    data: &'a [u8],
+    position: usize,
+    mode: StuffingMode
+}
+
+impl<'a> Unstuffer<'a> {
+    pub(crate) fn new(data: &'a [u8], mode: StuffingMode) -> Self {
+        Unstuffer { data, position: 0, mode }
+    }
+}
+
+impl Iterator for Unstuffer<'_> {
+    type Item = Result<u8, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match (*self.data.get(self.position)?, self.data.get(self.position + 1)) {
+            (0xFD, Some(next)) if *next <= 2 => {
+                self.position += 1;
+                0xFD + *next
+            },
+            (x, _) if x >= 0xFD && self.mode == StuffingMode::Strict => {
+                let position = self.position;
+                self.position = self.data.len();
+                return Some(Err(DecodeError::BadStuffing { position }));
+            },
+            (x, _) => x
+        };
+
+        self.position += 1;
+        Some(Ok(current))
+    }
+}
+
+// Writes the MEC, DSN/PSN, MEL and data fields of an element.
+// Returns the number of bytes written to `out`.
+pub fn encode_element_into(
+    element_type: MessageElementType,
+    dataset_number: u8,
+    program_service_number: u8,
+    data: &[u8],
+    out: &mut [u8]
+) -> Result<usize, EncodeError> {
+    match element_type.length_type {
+        LengthType::FixedLength(length) if data.len() != length => {
+            return Err(EncodeError::ElementLengthMismatch { mec: element_type.code, expected: length, actual: data.len() });
+        },
+        LengthType::RangeLength(min, max) if data.len() < min || data.len() > max => {
+            let expected = data.len().clamp(min, max);
+            return Err(EncodeError::ElementLengthMismatch { mec: element_type.code, expected, actual: data.len() });
+        },
+        _ if data.len() > 254 => return Err(EncodeError::ElementTooLarge),
+        _ => {}
+    }
+
+    let mut header = [0u8; 4];
+    let mut header_length: usize = 0;
+
+    // MEC field
+    header[0] = element_type.code;
+    header_length += 1;
+
+    // DSN and PSN fields
+    match element_type.dsn_psn_type {
+        DSNPSNType::None => {},
+        DSNPSNType::DSNOnly => {
+            header[1] = dataset_number;
+            header_length += 1;
+        },
+        DSNPSNType::All => {
+            header[1] = dataset_number;
+            header[2] = program_service_number;
+            header_length += 2;
+        }
+    }
+
+    // MEL (length) field
+    match element_type.length_type {
+        LengthType::VariableLength => {
+            header[header_length] = data.len() as u8;
+            header_length += 1;
+        }
+        LengthType::FixedLength(_) | LengthType::RangeLength(..) => {}
+    }
+
+    let total_length = header_length + data.len();
+    let target = out.get_mut(..total_length).ok_or(EncodeError::BufferTooSmall)?;
+    target[..header_length].copy_from_slice(&header[..header_length]);
+    target[header_length..].copy_from_slice(data);
+
+    Ok(total_length)
+}
+
+// Builds a complete frame around an already encoded message field.
+// Returns the number of bytes written to `out`.
+pub fn encode_frame_into(
+    site_address: u16,
+    encoder_address: u8,
+    sequence_counter: u8,
+    message: &[u8],
+    out: &mut [u8]
+) -> Result<usize, EncodeError> {
+    if message.len() > MAX_MESSAGE_LENGTH {
+        return Err(EncodeError::MessageTooLarge);
+    }
+
+    // Calculate the two-bytes ADD field
+    let address = address_field(site_address, encoder_address)?;
+
+    // Header fields and CRC, before stuffing
+    let mut header = [0u8; 4];
+    header[..2].copy_from_slice(&address.to_be_bytes()); // ADD
+    header[2] = sequence_counter; // SEQ
+    header[3] = message.len() as u8; // MEL (message element length)
+
+    let mut crc = Crc16::new();
+    crc.update(&header);
+    crc.update(message);
+    let crc = crc.finalize();
+
+    let mut written: usize = 0;
+    *out.get_mut(written).ok_or(EncodeError::BufferTooSmall)? = 0xFE;
+    written += 1;
+
+    for part in [&header[..], message, &crc.to_be_bytes()[..]].iter() {
+        let target = out.get_mut(written..).ok_or(EncodeError::BufferTooSmall)?;
+        written += stuff_into(part, target)?;
+    }
+
+    *out.get_mut(written).ok_or(EncodeError::BufferTooSmall)? = 0xFF;
+    written += 1;
+
+    Ok(written)
+}
+
+#[cfg(feature = "heapless")]
+pub fn encode_frame(
+    site_address: u16,
+    encoder_address: u8,
+    sequence_counter: u8,
+    message: &[u8]
+) -> Result<FrameBuffer, EncodeError> {
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+    let length = encode_frame_into(site_address, encoder_address, sequence_counter, message, &mut buffer)?;
+
+    FrameBuffer::from_slice(&buffer[..length]).map_err(|_| EncodeError::BufferTooSmall)
+}
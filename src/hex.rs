@@ -0,0 +1,36 @@
+// Hex text as written in profiles and on the command line, e.g. "4BD7 16 00"
+use core::fmt;
+use alloc::{ string::String, vec::Vec };
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HexError {
+    OddLength,
+    InvalidDigit(char)
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexError::OddLength => write!(f, "odd number of hex digits"),
+            HexError::InvalidDigit(c) => write!(f, "invalid hex digit {:?}", c)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HexError {}
+
+// Accepts hex digits with any whitespace in between, e.g. "FE 00 00 ..."
+pub fn parse_hex(text: &str) -> Result<Vec<u8>, HexError> {
+    let digits: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if let Some(c) = digits.iter().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(HexError::InvalidDigit(*c));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(HexError::OddLength);
+    }
+
+    Ok(digits.chunks(2)
+        .map(|pair| u8::from_str_radix(&pair.iter().collect::<String>(), 16).expect("checked hex digits"))
+        .collect())
+}
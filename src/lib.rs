@@ -1,10 +1,35 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[macro_use]
 extern crate num_derive;
 
 #[macro_use]
 extern crate phf;
 
+#[cfg(feature = "alloc")]
+pub mod batcher;
+#[cfg(feature = "alloc")]
+pub mod dataset;
 pub mod defs;
+#[cfg(feature = "alloc")]
+mod dissect;
+#[cfg(feature = "alloc")]
+pub mod elements;
 pub mod ebulatin;
+pub mod framing;
+pub mod frame_ref;
+#[cfg(feature = "alloc")]
+pub mod hex;
+#[cfg(feature = "profile")]
+pub mod profile;
+#[cfg(feature = "alloc")]
 pub mod protocol;
-pub mod logic;
\ No newline at end of file
+#[cfg(feature = "alloc")]
+pub mod logic;
+#[cfg(feature = "std")]
+pub mod metadata;
+#[cfg(feature = "alloc")]
+pub mod validation;
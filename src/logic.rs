@@ -1,4 +1,5 @@
-use std::ops::Fn;
+use core::ops::Fn;
+use alloc::{ vec, vec::Vec };
 use crate::defs::{ ResponseCode, MessageElementType, element_types };
 use crate::protocol::{ Frame, MessageElement };
 
@@ -7,6 +8,7 @@ pub fn process_incoming_frame<F>(request: &Frame, cb: F) -> Option<Frame>
 {
     let response_code = cb(request);
 
+    // The sequence number of the frame is only sent back with an error
     let mut ack_data: Vec<u8> = vec![response_code.as_u8()];
     if response_code != ResponseCode::Ok {
         ack_data.push(request.sequence_counter);
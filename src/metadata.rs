@@ -0,0 +1,344 @@
+// Now-playing metadata from playout automation, rendered into RT and RT+,
+// DAB DL and DL Plus, and optionally a dynamic PS.
+//
+// Templates refer to the event fields as {artist}, {title} and {album}, and to
+// the station slogan as {slogan}. Each text has a list of templates that are
+// tried in order: a template is skipped when one of its fields is missing,
+// or when the rendered text is longer than the element allows.
+use std::time::{ Duration, Instant };
+use crate::defs::{ GroupType, GroupVersion, EncodeError, element_types };
+use crate::ebulatin::{ can_encode, to_e1 };
+use crate::elements::dab::{ DabCharset, MAX_DYNAMIC_LABEL_LENGTH };
+use crate::elements::radiotext::MAX_RADIOTEXT_LENGTH;
+use crate::elements::tags::{ ContentType, TaggedText };
+use crate::protocol::MessageElement;
+
+pub const PS_LENGTH: usize = 8;
+
+// RT+ sends two tags per group, DL Plus up to four
+const MAX_RT_PLUS_TAGS: usize = 2;
+const MAX_DL_PLUS_TAGS: usize = 4;
+
+// The second RT+ tag has a 5-bit length marker
+const MAX_SECOND_RT_PLUS_TAG_LENGTH: u8 = 32;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NowPlaying {
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub album: Option<String>,
+    // The default texts come back once the item has played this long
+    pub duration: Option<Duration>
+}
+
+impl NowPlaying {
+    pub fn new(title: &str) -> Self {
+        NowPlaying { title: Some(title.to_string()), ..Default::default() }
+    }
+
+    pub fn artist(mut self, artist: &str) -> Self {
+        self.artist = Some(artist.to_string());
+        self
+    }
+
+    pub fn album(mut self, album: &str) -> Self {
+        self.album = Some(album.to_string());
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataConfig {
+    pub dataset_number: u8,
+    pub program_service_number: u8,
+    // Sent as RT and DL between items
+    pub slogan: String,
+    pub rt_templates: Vec<String>,
+    // RT+ tags are not sent when None
+    pub rt_plus_group: Option<GroupType>,
+    // DL and DL Plus are not sent when None
+    pub dab_charset: Option<DabCharset>,
+    pub dl_templates: Vec<String>,
+    // Static PS, sent between items and when there is no dynamic PS
+    pub ps: String,
+    // Splits into pages of 8 characters, shown one after the other
+    pub dynamic_ps_template: Option<String>,
+    pub ps_page_duration: Duration
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        MetadataConfig {
+            dataset_number: 0,
+            program_service_number: 0,
+            slogan: String::new(),
+            rt_templates: vec![String::from("{artist} - {title}"), String::from("{title}")],
+            rt_plus_group: Some(GroupType { number: 11, version: GroupVersion::A }),
+            dab_charset: None,
+            dl_templates: vec![
+                String::from("{artist} - {title} - {album}"),
+                String::from("{artist} - {title}"),
+                String::from("{title}")
+            ],
+            ps: String::new(),
+            dynamic_ps_template: None,
+            ps_page_duration: Duration::from_secs(4)
+        }
+    }
+}
+
+// Replaces what the target character set can't carry: typographic punctuation
+// by its plain form, other missing characters by '?', control characters by spaces
+pub fn sanitize(text: &str, charset: DabCharset) -> String {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_control() => ' ',
+            '\u{2018}' | '\u{2019}' if charset == DabCharset::EbuLatin => '\'',
+            '\u{201C}' | '\u{201D}' if charset == DabCharset::EbuLatin => '"',
+            '\u{2013}' | '\u{2014}' if charset == DabCharset::EbuLatin => '-',
+            c if charset == DabCharset::EbuLatin && !can_encode(c) => '?',
+            c if charset == DabCharset::Ucs2 && c.len_utf16() > 1 => '?',
+            c => c
+        })
+        .collect()
+}
+
+// None when the template uses a field the event doesn't have, or an unknown field
+pub fn render(template: &str, event: &NowPlaying, slogan: &str) -> Option<TaggedText> {
+    let mut text = TaggedText::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        text = text.plain(&rest[..start]);
+        let end = start + rest[start..].find('}')?;
+
+        let (value, content_type) = match &rest[start + 1..end] {
+            "artist" => (event.artist.as_deref(), Some(ContentType::ITEM_ARTIST)),
+            "title" => (event.title.as_deref(), Some(ContentType::ITEM_TITLE)),
+            "album" => (event.album.as_deref(), Some(ContentType::ITEM_ALBUM)),
+            "slogan" => (Some(slogan), None),
+            _ => return None
+        };
+
+        let value = value.filter(|x| !x.trim().is_empty())?.trim();
+        text = match content_type {
+            Some(content_type) => text.tagged(content_type, value),
+            None => text.plain(value)
+        };
+        rest = &rest[end + 1..];
+    }
+
+    Some(text.plain(rest))
+}
+
+// Cuts the text (and its tags) until `fits` accepts it
+fn truncate(mut text: TaggedText, fits: impl Fn(&str) -> bool) -> TaggedText {
+    while !fits(&text.text) {
+        text.text.pop();
+    }
+
+    let length = text.text.chars().count();
+    text.tags.retain(|x| (x.start as usize) < length);
+    for tag in text.tags.iter_mut() {
+        tag.length = tag.length.min((length - tag.start as usize) as u8);
+    }
+
+    text
+}
+
+// First template that renders and fits, or the last one that renders, truncated.
+// Falls back to the slogan when no template renders.
+fn fit(templates: &[String], event: &NowPlaying, slogan: &str, charset: DabCharset, fits: impl Fn(&str) -> bool) -> TaggedText {
+    let mut last: Option<TaggedText> = None;
+
+    for template in templates {
+        if let Some(mut text) = render(template, event, slogan) {
+            text.text = sanitize(&text.text, charset);
+            if fits(&text.text) {
+                return text;
+            }
+            last = Some(text);
+        }
+    }
+
+    let text = last.unwrap_or_else(|| TaggedText::new().plain(&sanitize(slogan, charset)));
+    truncate(text, fits)
+}
+
+fn radiotext_fits(text: &str) -> bool {
+    text.chars().count() <= MAX_RADIOTEXT_LENGTH
+}
+
+// Words packed into pages of up to 8 characters; longer words are cut
+pub fn ps_pages(text: &str) -> Vec<String> {
+    let mut pages: Vec<String> = vec![];
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let chars: Vec<char> = word.chars().collect();
+        for part in chars.chunks(PS_LENGTH) {
+            let part: String = part.iter().collect();
+            let length = current.chars().count();
+
+            if length > 0 && length + 1 + part.chars().count() <= PS_LENGTH {
+                current.push(' ');
+                current.push_str(&part);
+            } else {
+                if length > 0 {
+                    pages.push(current);
+                }
+                current = part;
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+// Keeps the on-air texts of one programme service in line with the playout.
+// Feed it now-playing events, and poll it from the sending loop so that
+// dynamic PS pages advance and the defaults come back when an item expires.
+pub struct MetadataBridge {
+    config: MetadataConfig,
+    ab_flag: bool,
+    item_toggle: bool,
+    expires: Option<Instant>,
+    ps_pages: Vec<String>,
+    ps_page: usize,
+    ps_next: Option<Instant>
+}
+
+impl MetadataBridge {
+    pub fn new(config: MetadataConfig) -> Self {
+        MetadataBridge {
+            config,
+            ab_flag: false,
+            item_toggle: false,
+            expires: None,
+            ps_pages: vec![],
+            ps_page: 0,
+            ps_next: None
+        }
+    }
+
+    pub fn config(&self) -> &MetadataConfig {
+        &self.config
+    }
+
+    // When the current item reverts to the default texts
+    pub fn expires(&self) -> Option<Instant> {
+        self.expires
+    }
+
+    // Slogan and static PS, with no item running
+    pub fn defaults(&mut self) -> Result<Vec<MessageElement>, EncodeError> {
+        self.expires = None;
+        self.ps_pages.clear();
+        self.ps_next = None;
+
+        let slogan = TaggedText::new().plain(&sanitize(&self.config.slogan, DabCharset::EbuLatin));
+        let mut text = truncate(slogan, radiotext_fits);
+        text.item_running = false;
+
+        let mut elements = self.text_elements(text, &[], &NowPlaying::default())?;
+        elements.push(self.ps_element(&self.config.ps.clone())?);
+        Ok(elements)
+    }
+
+    pub fn now_playing(&mut self, event: &NowPlaying, now: Instant) -> Result<Vec<MessageElement>, EncodeError> {
+        self.expires = event.duration.map(|x| now + x);
+        self.item_toggle = !self.item_toggle;
+
+        let rt_templates = self.config.rt_templates.clone();
+        let text = fit(&rt_templates, event, &self.config.slogan, DabCharset::EbuLatin, radiotext_fits);
+        let mut elements = self.text_elements(text, &self.config.dl_templates.clone(), event)?;
+
+        let pages = match &self.config.dynamic_ps_template {
+            Some(template) => render(template, event, &self.config.slogan)
+                .map(|x| ps_pages(&sanitize(&x.text, DabCharset::EbuLatin)))
+                .unwrap_or_default(),
+            None => vec![]
+        };
+
+        self.ps_pages = pages;
+        self.ps_page = 0;
+        self.ps_next = None;
+        match self.ps_pages.first().cloned() {
+            Some(page) => {
+                elements.push(self.ps_element(&page)?);
+                self.ps_next = Some(now + self.config.ps_page_duration);
+            },
+            // No dynamic PS for this item, so the previous item's pages are replaced
+            None => elements.push(self.ps_element(&self.config.ps.clone())?)
+        }
+
+        Ok(elements)
+    }
+
+    // Elements that are due at `now`: the defaults once the item has expired,
+    // otherwise the next dynamic PS page
+    pub fn poll(&mut self, now: Instant) -> Result<Vec<MessageElement>, EncodeError> {
+        if self.expires.is_some_and(|x| now >= x) {
+            return self.defaults();
+        }
+
+        match self.ps_next {
+            Some(next) if now >= next && self.ps_pages.len() > 1 => {
+                self.ps_page = (self.ps_page + 1) % self.ps_pages.len();
+                self.ps_next = Some(now + self.config.ps_page_duration);
+                let page = self.ps_pages[self.ps_page].clone();
+                Ok(vec![self.ps_element(&page)?])
+            },
+            _ => Ok(vec![])
+        }
+    }
+
+    // RT (toggling A/B for the new text) and RT+, then DL and DL Plus
+    fn text_elements(&mut self, mut text: TaggedText, dl_templates: &[String], event: &NowPlaying) -> Result<Vec<MessageElement>, EncodeError> {
+        self.ab_flag = !self.ab_flag;
+        text.item_toggle = self.item_toggle;
+        text.tags.truncate(MAX_RT_PLUS_TAGS);
+        if text.tags.len() == MAX_RT_PLUS_TAGS && text.tags[1].length > text.tags[0].length {
+            // Tags can come in any order, so the longer one goes where it has more room
+            text.tags.swap(0, 1);
+        }
+        if let Some(tag) = text.tags.get_mut(1) {
+            tag.length = tag.length.min(MAX_SECOND_RT_PLUS_TAG_LENGTH);
+        }
+
+        let mut elements = text.radiotext_elements(self.ab_flag, self.config.rt_plus_group)?;
+        elements[0].set_dsn_psn(self.config.dataset_number, self.config.program_service_number);
+
+        if let Some(charset) = self.config.dab_charset {
+            let fits = |x: &str| charset.encode(x).len() <= MAX_DYNAMIC_LABEL_LENGTH;
+            let mut label = fit(dl_templates, event, &self.config.slogan, charset, fits);
+            label.item_toggle = text.item_toggle;
+            label.item_running = text.item_running;
+            label.tags.truncate(MAX_DL_PLUS_TAGS);
+            elements.extend(label.dynamic_label_elements(charset)?);
+        }
+
+        Ok(elements)
+    }
+
+    fn ps_element(&self, text: &str) -> Result<MessageElement, EncodeError> {
+        let mut data: Vec<u8> = to_e1(&sanitize(text, DabCharset::EbuLatin));
+        if data.len() > PS_LENGTH {
+            return Err(EncodeError::TextTooLong);
+        }
+        data.resize(PS_LENGTH, b' ');
+
+        let mut element = MessageElement::new(element_types::PS, &data);
+        element.set_dsn_psn(self.config.dataset_number, self.config.program_service_number);
+        Ok(element)
+    }
+}
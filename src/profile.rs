@@ -0,0 +1,409 @@
+// Declarative station profiles (TOML), compiled into the commands that
+// provision one data set of an encoder:
+//
+//     site = 62
+//     encoder = 14
+//     group_sequence = "0A 2A 0A 3A"
+//
+//     [[service]]
+//     psn = 1
+//     pi = "C201"
+//     ps = "RADIO 1"
+//     pty = 10
+//     tp = true
+//     af = [98.5, 101.2]
+//     country = "DE"
+//     language = "de"
+//
+//     [[service.element]]
+//     type = "ODA_CONFIG"
+//     data = "4BD7 16 00 00 00 00"
+use core::convert::TryFrom;
+use core::fmt;
+use crate::batcher::FrameBatcher;
+use crate::dataset::{ Dataset, DatasetNumber };
+use crate::defs::{ PTY, DSNPSNType, EncodeError, element_types };
+use crate::ebulatin::to_e1;
+use crate::elements::flags::{ DecoderIdentification, MusicSpeech, TrafficFlags };
+use crate::elements::group_sequence::{ GroupSequence, GroupSequenceError };
+use crate::elements::slow_labeling::SlowLabeling;
+use crate::hex::parse_hex;
+use crate::protocol::{ Frame, MessageElement, SequenceCounter };
+use num_traits::FromPrimitive;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileError {
+    // Not valid TOML, or not shaped like a profile
+    Syntax(String),
+    // A field holds a value that cannot be sent; psn is None for data set-wide fields
+    InvalidValue { psn: Option<u8>, field: &'static str },
+    DuplicateService(u8),
+    GroupSequence(GroupSequenceError),
+    Encode(EncodeError)
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Syntax(message) => write!(f, "invalid profile: {}", message),
+            ProfileError::InvalidValue { psn: Some(psn), field } => write!(f, "invalid {} for service {}", field, psn),
+            ProfileError::InvalidValue { psn: None, field } => write!(f, "invalid {}", field),
+            ProfileError::DuplicateService(psn) => write!(f, "service {} is defined twice", psn),
+            ProfileError::GroupSequence(e) => write!(f, "invalid group_sequence: {}", e),
+            ProfileError::Encode(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<EncodeError> for ProfileError {
+    fn from(value: EncodeError) -> Self {
+        match value {
+            EncodeError::DuplicateService(psn) => ProfileError::DuplicateService(psn),
+            e => ProfileError::Encode(e)
+        }
+    }
+}
+
+// Element given as its registered type name and hex data, for everything
+// the profile has no dedicated field for (ODAs, TMC, ...)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawElement {
+    #[serde(rename = "type")]
+    pub element_type: String,
+    #[serde(default)]
+    pub data: String
+}
+
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceProfile {
+    pub psn: u8,
+    // Hex, e.g. "C201"
+    pub pi: String,
+    #[serde(default)]
+    pub ps: Option<String>,
+    #[serde(default)]
+    pub pty: Option<u8>,
+    #[serde(default)]
+    pub ptyn: Option<String>,
+    #[serde(default)]
+    pub di: Option<DecoderIdentification>,
+    // Music (true) or speech (false)
+    #[serde(default)]
+    pub music: Option<bool>,
+    #[serde(default)]
+    pub tp: Option<bool>,
+    #[serde(default)]
+    pub ta: Option<bool>,
+    // Alternative frequencies in MHz, sent as an AF method A list
+    #[serde(default)]
+    pub af: Vec<f64>,
+    // ISO 3166-1 country, sent as the ECC matching the PI
+    #[serde(default)]
+    pub country: Option<String>,
+    // ISO 639-1 language, sent as the LIC
+    #[serde(default)]
+    pub language: Option<String>,
+    // Enables the EON elements of this service
+    #[serde(default)]
+    pub eon: Option<bool>,
+    #[serde(default, rename = "element")]
+    pub elements: Vec<RawElement>
+}
+
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub site: u16,
+    #[serde(default)]
+    pub encoder: u8,
+    // Group types, e.g. "0A 2A 0A 3A"
+    #[serde(default)]
+    pub group_sequence: Option<String>,
+    #[serde(default, rename = "service")]
+    pub services: Vec<ServiceProfile>,
+    // Data set-wide elements
+    #[serde(default, rename = "element")]
+    pub elements: Vec<RawElement>
+}
+
+// Eight characters, padded with spaces
+fn label(text: &str) -> Option<Vec<u8>> {
+    let mut data = to_e1(text);
+    if data.len() > 8 {
+        return None;
+    }
+
+    data.resize(8, b' ');
+    Some(data)
+}
+
+// 87.6 to 107.9 MHz in 100 kHz steps, codes 1 to 204
+fn af_code(frequency: f64) -> Option<u8> {
+    let code = ((frequency - 87.5) * 10.0).round();
+    if !(1.0..=204.0).contains(&code) || ((87.5 + code / 10.0) - frequency).abs() > 0.01 {
+        return None;
+    }
+
+    Some(code as u8)
+}
+
+fn raw_element(raw: &RawElement, psn: Option<u8>) -> Result<MessageElement, ProfileError> {
+    let element_type = element_types::from_name(&raw.element_type)
+        .ok_or(ProfileError::InvalidValue { psn, field: "element type" })?;
+    let data = parse_hex(&raw.data)
+        .map_err(|_| ProfileError::InvalidValue { psn, field: "element data" })?;
+
+    let element = MessageElement::new(element_type, &data);
+    element.into_bytes()?;
+    Ok(element)
+}
+
+impl ServiceProfile {
+    pub fn pi_code(&self) -> Result<u16, ProfileError> {
+        let invalid = ProfileError::InvalidValue { psn: Some(self.psn), field: "pi" };
+        if self.pi.len() != 4 {
+            return Err(invalid);
+        }
+
+        u16::from_str_radix(&self.pi, 16).map_err(|_| invalid)
+    }
+
+    fn elements(&self) -> Result<Vec<MessageElement>, ProfileError> {
+        let psn = Some(self.psn);
+        let invalid = |field: &'static str| ProfileError::InvalidValue { psn, field };
+        let pi = self.pi_code()?;
+        let mut elements: Vec<MessageElement> = vec![];
+
+        elements.push(MessageElement::new(element_types::PI, &pi.to_be_bytes()));
+
+        if let Some(ps) = &self.ps {
+            elements.push(MessageElement::new(element_types::PS, &label(ps).ok_or_else(|| invalid("ps"))?));
+        }
+
+        if let Some(pty) = self.pty {
+            elements.push(PTY::from_u8(pty).ok_or_else(|| invalid("pty"))?.into());
+        }
+
+        if let Some(ptyn) = &self.ptyn {
+            elements.push(MessageElement::new(element_types::PTYN, &label(ptyn).ok_or_else(|| invalid("ptyn"))?));
+        }
+
+        if let Some(di) = self.di {
+            elements.push(di.into());
+        }
+
+        if let Some(music) = self.music {
+            elements.push(if music { MusicSpeech::Music } else { MusicSpeech::Speech }.into());
+        }
+
+        if self.tp.is_some() || self.ta.is_some() {
+            elements.push(TrafficFlags::new(self.tp.unwrap_or(false), self.ta.unwrap_or(false)).into());
+        }
+
+        if !self.af.is_empty() {
+            if self.af.len() > 25 {
+                return Err(invalid("af"));
+            }
+
+            let mut data: Vec<u8> = vec![0xE0 + self.af.len() as u8];
+            for frequency in self.af.iter() {
+                data.push(af_code(*frequency).ok_or_else(|| invalid("af"))?);
+            }
+            elements.push(MessageElement::new(element_types::AF, &data));
+        }
+
+        if let Some(country) = &self.country {
+            let ecc = SlowLabeling::extended_country_code(country, pi).ok_or_else(|| invalid("country"))?;
+            elements.push(MessageElement::try_from(ecc)?);
+        }
+
+        if let Some(language) = &self.language {
+            let lic = SlowLabeling::language(language).ok_or_else(|| invalid("language"))?;
+            elements.push(MessageElement::try_from(lic)?);
+        }
+
+        if let Some(eon) = self.eon {
+            elements.push(MessageElement::new(element_types::EON_ELEMENTS_TOGGLE, &[eon as u8]));
+        }
+
+        for raw in self.elements.iter() {
+            elements.push(raw_element(raw, psn)?);
+        }
+
+        Ok(elements)
+    }
+}
+
+// Identifies what an element configures, so that two compiled profiles can be compared.
+// Slow labelling variants are separate settings; other repeated elements (raw ones)
+// are told apart by their position.
+type ElementKey = (u8, u8, u8, u8, usize);
+
+// PSN of the setting an element changes. Elements without a PSN field (e.g. ODA_CONFIG
+// listed under a service) configure the whole data set or encoder, as if sent with PSN 0.
+fn setting_psn(element: &MessageElement) -> u8 {
+    match element.element_type.dsn_psn_type {
+        DSNPSNType::All => element.program_service_number,
+        _ => 0
+    }
+}
+
+fn keyed(elements: Vec<MessageElement>) -> Vec<(ElementKey, MessageElement)> {
+    let mut result: Vec<(ElementKey, MessageElement)> = vec![];
+
+    for element in elements {
+        let variant = if element.element_type.code == element_types::SLOW_LABELING.code {
+            element.data.first().map_or(0, |x| (x >> 4) & 0x07)
+        } else {
+            0
+        };
+
+        let mut key = (element.element_type.code, element.dataset_number, setting_psn(&element), variant, 0);
+        key.4 = result.iter().filter(|(x, _)| (x.0, x.1, x.2, x.3) == (key.0, key.1, key.2, key.3)).count();
+        result.push((key, element));
+    }
+
+    result
+}
+
+// Command that puts a setting missing from the target profile back to its neutral
+// value, where there is one. Raw elements are kept as they are.
+fn reset_element(element: &MessageElement) -> Option<MessageElement> {
+    let code = element.element_type.code;
+    let slow_labeling_variant = [element.data.first().map_or(0, |x| x & 0x70), 0x00];
+    let data: &[u8] = match code {
+        c if c == element_types::AF.code => &[0xE0],
+        c if c == element_types::PS.code || c == element_types::PTYN.code => b"        ",
+        c if c == element_types::PTY.code || c == element_types::DI.code => &[0x00],
+        c if c == element_types::MS.code => &[MusicSpeech::Music as u8],
+        c if c == element_types::TA_TP.code || c == element_types::EON_ELEMENTS_TOGGLE.code => &[0x00],
+        // ECC and LIC 0 mean "not specified"
+        c if c == element_types::SLOW_LABELING.code => &slow_labeling_variant,
+        c if c == element_types::MAKE_PSN_LIST.code || c == element_types::GROUP_SEQUENCE.code => &[],
+        _ => return None
+    };
+
+    let mut reset = MessageElement::new(element.element_type, data);
+    reset.set_dsn_psn(element.dataset_number, element.program_service_number);
+    Some(reset)
+}
+
+// Commands that move an encoder from one profile to another
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProfileDiff {
+    pub elements: Vec<MessageElement>,
+    // Settings missing from the target that no command can clear,
+    // as they were sent for the current profile
+    pub unresettable: Vec<MessageElement>
+}
+
+impl Profile {
+    pub fn from_toml(source: &str) -> Result<Self, ProfileError> {
+        let profile: Profile = toml::from_str(source).map_err(|e| ProfileError::Syntax(e.message().to_string()))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ProfileError> {
+        toml::to_string(self).map_err(|e| ProfileError::Syntax(e.to_string()))
+    }
+
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        self.elements(0).map(|_| ())
+    }
+
+    // Every command of the profile, in the order they should be sent
+    pub fn elements(&self, dataset_number: u8) -> Result<Vec<MessageElement>, ProfileError> {
+        Frame::get_address_field(self.site, self.encoder)?;
+
+        let mut dataset = Dataset::new(DatasetNumber::from_u8(dataset_number));
+        for service in self.services.iter() {
+            dataset.add_service(service.psn)?;
+        }
+
+        let mut elements: Vec<MessageElement> = vec![];
+        if !self.services.is_empty() {
+            elements.push(dataset.make_psn_list()?);
+        }
+
+        if let Some(source) = &self.group_sequence {
+            let sequence = GroupSequence::parse(source).map_err(ProfileError::GroupSequence)?;
+            let mut element = sequence.into_element()?;
+            element.set_dsn_psn(dataset_number, 0);
+            elements.push(element);
+        }
+
+        for service in self.services.iter() {
+            for mut element in service.elements()? {
+                dataset.target(&mut element, service.psn)?;
+                elements.push(element);
+            }
+        }
+
+        for raw in self.elements.iter() {
+            let mut element = raw_element(raw, None)?;
+            element.set_dsn_psn(dataset_number, 0);
+            elements.push(element);
+        }
+
+        Ok(elements)
+    }
+
+    pub fn compile(&self, dataset_number: u8, sequence: &mut SequenceCounter) -> Result<Vec<Frame>, ProfileError> {
+        Self::batch(self.site, self.encoder, self.elements(dataset_number)?, sequence)
+    }
+
+    // Commands that move an encoder configured with this profile to `target`:
+    // changed and new settings, plus a reset to the neutral value for settings
+    // missing from `target`. Services missing from `target` are dropped through
+    // the new PSN list.
+    pub fn diff(&self, target: &Profile, dataset_number: u8) -> Result<ProfileDiff, ProfileError> {
+        let current = keyed(self.elements(dataset_number)?);
+        let wanted = keyed(target.elements(dataset_number)?);
+        let services: Vec<u8> = target.services.iter().map(|x| x.psn).collect();
+
+        let mut diff = ProfileDiff::default();
+        for (key, element) in wanted.iter() {
+            if !current.iter().any(|(x, old)| x == key && old.data == element.data) {
+                diff.elements.push(element.clone());
+            }
+        }
+
+        for (key, element) in current.iter() {
+            // PSN 0 holds the data set-wide settings
+            let psn = setting_psn(element);
+            let kept = psn == 0 || services.contains(&psn);
+            if !kept || wanted.iter().any(|(x, _)| x == key) {
+                continue;
+            }
+
+            match reset_element(element) {
+                Some(reset) => diff.elements.push(reset),
+                None => diff.unresettable.push(element.clone())
+            }
+        }
+
+        Ok(diff)
+    }
+
+    // Frames of `diff`, with the settings it cannot clear
+    pub fn diff_frames(&self, target: &Profile, dataset_number: u8, sequence: &mut SequenceCounter) -> Result<(Vec<Frame>, Vec<MessageElement>), ProfileError> {
+        let diff = self.diff(target, dataset_number)?;
+        let frames = Self::batch(target.site, target.encoder, diff.elements, sequence)?;
+        Ok((frames, diff.unresettable))
+    }
+
+    fn batch(site: u16, encoder: u8, elements: Vec<MessageElement>, sequence: &mut SequenceCounter) -> Result<Vec<Frame>, ProfileError> {
+        let mut batcher = FrameBatcher::new(site, encoder);
+        for element in elements {
+            batcher.push(element);
+        }
+
+        Ok(batcher.into_frames(sequence)?)
+    }
+}
@@ -1,9 +1,20 @@
-use std::sync::atomic::{ AtomicU8, Ordering };
-use bytebuffer::ByteBuffer;
-use crate::defs::{ LengthType, DSNPSNType, MessageElementType, EncodeError, DecodeError, element_types };
-
-static GLOBAL_SEQUENCE_NUMBER: AtomicU8 = AtomicU8::new(1);
-
+use core::convert::TryFrom;
+use alloc::{ vec, vec::Vec, string::String };
+use crate::defs::{ MessageElementType, EncodeError, DecodeError, element_types };
+use crate::ebulatin::from_e1;
+use crate::elements::dab::DynamicLabel;
+use crate::elements::radiotext::RadioText;
+use crate::framing::{ self, StuffingMode };
+use crate::frame_ref::{ ElementRef, FrameRef };
+use crate::elements::TypedMessageElement;
+use crate::elements::specific::SpecificCommandRegistry;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "MessageElementFields", from = "MessageElementFields")
+)]
 pub struct MessageElement {
     pub element_type: MessageElementType,
     pub dataset_number: u8,
@@ -11,6 +22,46 @@ pub struct MessageElement {
     pub data: Vec<u8>
 }
 
+// Serialized form, with the decoded text next to the data for text elements.
+// The text is only there to be read: deserializing uses the data.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MessageElementFields {
+    element_type: MessageElementType,
+    dataset_number: u8,
+    program_service_number: u8,
+    data: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>
+}
+
+#[cfg(feature = "serde")]
+impl From<MessageElement> for MessageElementFields {
+    fn from(value: MessageElement) -> Self {
+        MessageElementFields {
+            text: value.text(),
+            element_type: value.element_type,
+            dataset_number: value.dataset_number,
+            program_service_number: value.program_service_number,
+            data: value.data
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<MessageElementFields> for MessageElement {
+    fn from(value: MessageElementFields) -> Self {
+        MessageElement {
+            element_type: value.element_type,
+            dataset_number: value.dataset_number,
+            program_service_number: value.program_service_number,
+            data: value.data
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
     pub sequence_counter: u8,
     pub site_address: u16,
@@ -18,6 +69,14 @@ pub struct Frame {
     pub elements: Vec<MessageElement>
 }
 
+// Sequence numbers of the frames sent over one link.
+// 0 means "no sequence checking", so a counting link wraps from 255 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceCounter {
+    next: u8,
+    checking: bool
+}
+
 impl MessageElement {
     pub fn new(element_type: MessageElementType, data: &[u8]) -> MessageElement {
         MessageElement {
@@ -28,82 +87,112 @@ impl MessageElement {
         }
     }
 
-    pub fn from_bytes(bytes: &[u8]) -> Result<MessageElement, DecodeError> {
-        let mut buffer = ByteBuffer::from_bytes(bytes);
-
-        let first_byte = buffer.read_u8();
-        let element_type = element_types::from_code(&first_byte)
-            .map_or(Err(DecodeError::UnknownElementType(first_byte)), |x| Ok(x))?;
-
-        let dataset_number: u8 = match element_type.dsn_psn_type {
-            DSNPSNType::DSNOnly | DSNPSNType::All => buffer.read_u8(),
-            _ => 0
-        };
-
-        let program_service_number: u8 = match element_type.dsn_psn_type {
-            DSNPSNType::All => buffer.read_u8(),
-            _ => 0
-        };
-
-        match element_type.length_type {
-            LengthType::VariableLength => {
-                // Skip MEL field
-                buffer.read_u8();
-            },
-            LengthType::FixedLength(_) => {}
+    pub fn set_dsn_psn(&mut self, dataset_number: u8, program_service_number: u8) {
+        self.dataset_number = dataset_number;
+        self.program_service_number = program_service_number;
+    }
+
+    // Decoded text of the text elements (PS, PTYN, RT and DL), None for the others
+    pub fn text(&self) -> Option<String> {
+        let code = self.element_type.code;
+
+        if code == element_types::PS.code || code == element_types::PTYN.code {
+            Some(from_e1(&self.data))
+        } else if code == element_types::RT.code {
+            RadioText::try_from(self).ok().map(|x| x.text)
+        } else if code == element_types::DAB_DL_MESSAGE.code {
+            DynamicLabel::try_from(self).ok().map(|x| x.text)
+        } else {
+            None
         }
+    }
 
-        let data: Vec<u8> = buffer.read_bytes(buffer.len() - buffer.get_rpos());
+    // Decodes exactly one element; its data must match the fixed length or MEL
+    pub fn from_bytes(bytes: &[u8]) -> Result<MessageElement, DecodeError> {
+        let (element, element_length) = ElementRef::parse(bytes)?;
+
+        if element_length != bytes.len() {
+            let header_length = element_length - element.data.len();
+            return Err(DecodeError::ElementLengthMismatch {
+                mec: element.element_type.code,
+                expected: element.data.len(),
+                actual: bytes.len() - header_length
+            });
+        }
 
-        Ok(MessageElement {
-            element_type,
-            dataset_number,
-            program_service_number,
-            data
-        })
+        Ok(MessageElement::from(element))
     }
 
     pub fn into_bytes(&self) -> Result<Vec<u8>, EncodeError> {
-        if self.data.len() > 254 {
-            return Err(EncodeError::ElementTooLarge)
-        }
+        let mut buffer = [0u8; framing::MAX_ELEMENT_LENGTH];
+        let length = framing::encode_element_into(
+            self.element_type,
+            self.dataset_number,
+            self.program_service_number,
+            &self.data,
+            &mut buffer
+        )?;
+
+        Ok(buffer[..length].to_vec())
+    }
+}
 
-        let mut buffer = ByteBuffer::new();
-
-        // MEC field
-        buffer.write_u8(self.element_type.code);
-
-        // DSN and PSN fields
-        match self.element_type.dsn_psn_type {
-            DSNPSNType::None => {},
-            DSNPSNType::DSNOnly => {
-                buffer.write_u8(self.dataset_number);
-            },
-            DSNPSNType::All => {
-                buffer.write_u8(self.dataset_number);
-                buffer.write_u8(self.program_service_number);
-            }
-        }
+impl SequenceCounter {
+    pub fn new() -> Self {
+        SequenceCounter { next: 1, checking: true }
+    }
+
+    // Every frame is sent with sequence number 0
+    pub fn without_checking() -> Self {
+        SequenceCounter { next: 0, checking: false }
+    }
 
-        // MEL (length) field
-        match self.element_type.length_type {
-            LengthType::VariableLength => {
-                buffer.write_u8(self.data.len() as u8);
-            }
-            LengthType::FixedLength(_) => {}
+    pub fn starting_at(first: u8) -> Self {
+        SequenceCounter { next: first.max(1), checking: true }
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.checking
+    }
+
+    pub fn peek(&self) -> u8 {
+        self.next
+    }
+
+    pub fn allocate(&mut self) -> u8 {
+        let current = self.next;
+        if self.checking {
+            self.next = current.checked_add(1).unwrap_or(1);
         }
+        current
+    }
+
+    pub fn next_frame(&mut self) -> Frame {
+        Frame::with_sequence(self.allocate())
+    }
+}
 
-        // Element data
-        buffer.write_bytes(&self.data);
+impl Default for SequenceCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        Ok(buffer.to_bytes())
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Frame {
+    // Frame without sequence checking; use a SequenceCounter to number frames
     pub fn new() -> Self {
+        Self::with_sequence(0)
+    }
+
+    pub fn with_sequence(sequence_counter: u8) -> Self {
         Self {
-            sequence_counter: GLOBAL_SEQUENCE_NUMBER.fetch_add(1, Ordering::SeqCst),
+            sequence_counter,
             site_address: 0,
             encoder_address: 0,
             elements: vec![]
@@ -111,42 +200,12 @@ impl Frame {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Frame, DecodeError> {
-        // Remove byte stuffing before passing the bytes to the bytebuffer
-        let last_index = bytes.len() - 1;
-        let unstuffed_bytes = Self::revert_byte_stuffing(&bytes[1..last_index]); // STA and STP are ignored
-        let mut buffer = ByteBuffer::from_bytes(&unstuffed_bytes);
-
-        // Fetch data needed by the CRC computation (from the beginning to before the CRC field)
-        let crc_data = buffer.read_bytes(buffer.len() - 2);
-        let frame_crc = buffer.read_u16();
-
-        // Compute CRC
-        let computed_crc = Self::compute_crc16_genibus(&crc_data);
-        if computed_crc != frame_crc {
-            return Err(DecodeError::CRCError);
-        }
-
-        // Then come back to the beginning
-        buffer.set_rpos(0);
-
-        let address = buffer.read_u16();
-        let site_address: u16 = (address & 0xFFC0) >> 6 as u16;
-        let encoder_address: u8 = (address & 0x3F) as u8;
-
-        let sequence_counter = buffer.read_u8();
-        let message_length = buffer.read_u8() as usize;
-        if message_length > 255 {
-            return Err(DecodeError::MessageTooLarge);
-        }
-
-        let message = buffer.read_bytes(message_length);
+        Self::from_bytes_with(bytes, StuffingMode::Strict)
+    }
 
-        Ok(Frame {
-            sequence_counter,
-            site_address,
-            encoder_address,
-            elements: Frame::decode_message_field(&message)?
-        })
+    pub fn from_bytes_with(bytes: &[u8], stuffing: StuffingMode) -> Result<Frame, DecodeError> {
+        let mut scratch: Vec<u8> = vec![0u8; bytes.len()];
+        Frame::try_from(FrameRef::parse_with(bytes, &mut scratch, stuffing)?)
     }
 
     pub fn into_bytes(&self) -> Result<Vec<u8>, EncodeError> {
@@ -157,35 +216,25 @@ impl Frame {
             message_bytes.append(&mut element_bytes);
         }
 
-        if message_bytes.len() > 255 {
-            return Err(EncodeError::MessageTooLarge);
-        }
+        let mut frame = vec![0u8; framing::MAX_FRAME_LENGTH];
+        let length = framing::encode_frame_into(
+            self.site_address,
+            self.encoder_address,
+            self.sequence_counter,
+            &message_bytes,
+            &mut frame
+        )?;
+        frame.truncate(length);
+
+        Ok(frame)
+    }
 
-        // Calculate the two-bytes ADD field
-        let addr_field: u16 = Self::get_address_field(self.site_address, self.encoder_address)?;
-
-        // Start building the UECP frame
-        let mut frame = ByteBuffer::new();
-        frame.write_u16(addr_field); // ADD
-        frame.write_u8(self.sequence_counter); // SEQ
-        frame.write_u8(message_bytes.len() as u8); // MEL (message element length)
-        frame.write_bytes(&message_bytes); // Message
-
-        // Calculate the CRC value mid-way
-        let crc = Self::compute_crc16_genibus(&frame.to_bytes());
-        frame.write_u16(crc);
-        
-        // Apply stuffing
-        let stuffed_frame = Self::apply_byte_stuffing(&frame.to_bytes());
-
-        // Build the final frame
-        let mut final_frame = ByteBuffer::new();
-        final_frame.write_u8(0xFE);
-        final_frame.write_bytes(&stuffed_frame);
-        final_frame.write_u8(0xFF);
-
-        // And voilà
-        Ok(final_frame.to_bytes())
+    // Decodes every element on its own, so that one element with invalid data
+    // doesn't hide the others
+    pub fn typed_elements(&self, registry: Option<&SpecificCommandRegistry>) -> Vec<Result<TypedMessageElement, DecodeError>> {
+        self.elements.iter()
+            .map(|element| TypedMessageElement::decode(element, registry))
+            .collect()
     }
 
     pub fn set_addresses(&mut self, site_address: u16, encoder_address: u8) {
@@ -194,90 +243,51 @@ impl Frame {
     }
 
     pub fn get_address_field(site_address: u16, encoder_address: u8) -> Result<u16, EncodeError> {
-        if site_address > 1023 {
-            return Err(EncodeError::InvalidSiteAddress);
-        }
-
-        if encoder_address > 64 {
-            return Err(EncodeError::InvalidEncoderAddress);
-        }
-        
-        let mut address: u16;
-        address = (site_address & 0x3FF) << 6;
-        address |= (encoder_address & 0x3F) as u16;
-
-        Ok(address)
+        framing::address_field(site_address, encoder_address)
     }
 
     pub fn compute_crc16_genibus(data: &[u8]) -> u16 {
-        let mut crc: u16 = 0xFFFF;
-
-        // I have not idea what's happening here
-        // Took from https://github.com/UoC-Radio/rds-control/blob/master/uecp.c#L50-L65
-        for c in data {
-            crc = (crc >> 8) | (crc << 8);
-            crc ^= *c as u16;
-            crc ^= (crc & 0xFF) >> 4;
-            crc ^= (crc << 8) << 4;
-            crc ^= ((crc & 0xFF) << 4) << 1;
-        }
-
-        crc ^ 0xFFFF
+        framing::compute_crc16_genibus(data)
     }
 
     pub fn apply_byte_stuffing(data: &[u8]) -> Vec<u8> {
-        let mut result: Vec<u8> = vec![];
-        
-        for c in data {
-            let current = *c;
-
-            if current >= 0xFD {
-                result.push(0xFD);
-                result.push(current - 0xFD);
-            } else {
-                result.push(current);
-            }
-        }
-
+        let mut result: Vec<u8> = vec![0u8; data.len() * 2];
+        let length = framing::stuff_into(data, &mut result)
+            .expect("stuffed data is at most twice as long");
+        result.truncate(length);
         result
     }
 
-    pub fn revert_byte_stuffing(data: &[u8]) -> Vec<u8> {
-        let mut result: Vec<u8> = vec![];
-
-        for (index, c) in data.iter().enumerate() {
-            if index > 0 && data[index-1] == 0xFD {
-                continue;
-            }
+    pub fn revert_byte_stuffing(data: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut result: Vec<u8> = vec![0u8; data.len()];
+        let length = framing::unstuff_into(data, &mut result)?;
+        result.truncate(length);
+        Ok(result)
+    }
+}
 
-            let current = *c;
-            if current == 0xFD {
-                result.push(0xFD + data[index+1]);
-            } else {
-                result.push(current);
-            }
+impl From<ElementRef<'_>> for MessageElement {
+    fn from(value: ElementRef<'_>) -> Self {
+        MessageElement {
+            element_type: value.element_type,
+            dataset_number: value.dataset_number,
+            program_service_number: value.program_service_number,
+            data: value.data.to_vec()
         }
-
-        result
     }
+}
 
-    fn decode_message_field(bytes: &[u8]) -> Result<Vec<MessageElement>, DecodeError> {
-        let mut result: Vec<MessageElement> = vec![];
-
-        let last_index = bytes.len();
-        let mut i: usize = 0;
-        while i < bytes.len() {
-            let readable_bytes = bytes.get(i..last_index)
-                .ok_or_else(|| DecodeError::MessageTooShort)?;
-
-            let element_length = MessageElementType::get_next_element_length(readable_bytes)?;
-            result.push(
-                MessageElement::from_bytes(readable_bytes)?
-            );
-
-            i += element_length;
-        }
+impl TryFrom<FrameRef<'_>> for Frame {
+    type Error = DecodeError;
 
-        Ok(result)
+    fn try_from(value: FrameRef<'_>) -> Result<Self, Self::Error> {
+        Ok(Frame {
+            sequence_counter: value.sequence_counter,
+            site_address: value.site_address,
+            encoder_address: value.encoder_address,
+            elements: value.elements()
+                .map(|element| element.map(MessageElement::from))
+                .collect::<Result<Vec<MessageElement>, DecodeError>>()?
+        })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,65 @@
+use core::convert::TryFrom;
+use alloc::{ vec, vec::Vec };
+use crate::defs::{ PTY, element_types };
+use crate::protocol::{ Frame, MessageElement };
+use crate::elements::flags::TrafficFlags;
+
+// Inconsistencies that still produce a valid frame, but most likely not what was intended
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ValidationWarning {
+    // TA is set while TP is cleared, which only makes sense when EON carries a traffic programme
+    TaWithoutTp { dsn: u8, psn: u8 },
+    // A DI, MS or TA/TP element has bits set outside of its defined range
+    ReservedBitsSet { mec: u8, dsn: u8, psn: u8 },
+    // PTY is set to Alarm or AlarmTest while the same frame clears TA on that service
+    AlarmWithoutTa { dsn: u8, psn: u8 }
+}
+
+pub fn validate_frame(frame: &Frame) -> Vec<ValidationWarning> {
+    validate_elements(&frame.elements)
+}
+
+pub fn validate_elements(elements: &[MessageElement]) -> Vec<ValidationWarning> {
+    let mut warnings: Vec<ValidationWarning> = vec![];
+    let mut alarms: Vec<(u8, u8)> = vec![];
+    let mut cleared_ta: Vec<(u8, u8)> = vec![];
+
+    for element in elements {
+        let dsn = element.dataset_number;
+        let psn = element.program_service_number;
+
+        if let Ok(PTY::Alarm) | Ok(PTY::AlarmTest) = PTY::try_from(element) {
+            alarms.push((dsn, psn));
+        }
+
+        let reserved_mask: u8 = match element.element_type.code {
+            code if code == element_types::DI.code => 0xF0,
+            code if code == element_types::MS.code => 0xFE,
+            code if code == element_types::TA_TP.code => 0xFC,
+            _ => continue
+        };
+
+        if element.data.first().is_some_and(|x| x & reserved_mask != 0) {
+            warnings.push(ValidationWarning::ReservedBitsSet { mec: element.element_type.code, dsn, psn });
+            continue;
+        }
+
+        match TrafficFlags::try_from(element) {
+            Ok(TrafficFlags::EonTrafficProgramme) => {
+                warnings.push(ValidationWarning::TaWithoutTp { dsn, psn });
+            },
+            Ok(flags) if !flags.ta() => {
+                cleared_ta.push((dsn, psn));
+            },
+            _ => {}
+        }
+    }
+
+    for (dsn, psn) in alarms {
+        if cleared_ta.contains(&(dsn, psn)) {
+            warnings.push(ValidationWarning::AlarmWithoutTa { dsn, psn });
+        }
+    }
+
+    warnings
+}
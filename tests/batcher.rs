@@ -0,0 +1,103 @@
+use uecp_rs::batcher::*;
+use uecp_rs::defs::*;
+use uecp_rs::protocol::*;
+
+fn radiotext(length: usize) -> MessageElement {
+    // RT element: MEC, DSN, PSN, MEL, then the data
+    MessageElement::new(element_types::RT, &vec![0x41; length])
+}
+
+#[test]
+fn test_batcher_single_frame() {
+    let mut batcher = FrameBatcher::new(12, 3);
+    batcher.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    batcher.push(MessageElement::new(element_types::PTY, &[10]));
+
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].site_address, 12);
+    assert_eq!(frames[0].encoder_address, 3);
+    assert_eq!(frames[0].elements.len(), 2);
+}
+
+#[test]
+fn test_batcher_splits_in_order() {
+    let mut batcher = FrameBatcher::new(0, 0);
+    let elements: Vec<MessageElement> = (0..5).map(|x| radiotext(60 + x)).collect();
+    for element in elements.iter() {
+        batcher.push(element.clone());
+    }
+    assert_eq!(batcher.len(), 5);
+
+    // 64 to 68 bytes per element: three fit in the first frame
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].elements.len(), 3);
+    assert_eq!(frames[1].elements.len(), 2);
+
+    let decoded: Vec<MessageElement> = frames.iter().flat_map(|x| x.elements.clone()).collect();
+    assert_eq!(decoded, elements);
+
+    for frame in frames.iter() {
+        let bytes = frame.into_bytes().unwrap();
+        assert_eq!(Frame::from_bytes(&bytes).unwrap().elements, frame.elements);
+    }
+
+    assert_eq!(frames[0].sequence_counter, 1);
+    assert_eq!(frames[1].sequence_counter, 2);
+}
+
+#[test]
+fn test_batcher_keeps_text_tags() {
+    let tags = MessageElement::new(element_types::FREE_FORMAT, &[0x16, 0x08, 0x20, 0x0A, 0x00, 0x00]);
+
+    let mut batcher = FrameBatcher::new(0, 0);
+    batcher.push(radiotext(205));
+    batcher.push(radiotext(40));
+    batcher.push(tags.clone());
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].elements.len(), 2);
+    assert_eq!(frames[1].elements.len(), 1);
+
+    let rt_plus_group = GroupType::new(11, GroupVersion::A);
+    let mut batcher = FrameBatcher::new(0, 0).keep_text_tags(rt_plus_group);
+    batcher.push(radiotext(205));
+    batcher.push(radiotext(40));
+    batcher.push(tags.clone());
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].elements.len(), 1);
+    assert_eq!(frames[1].elements, vec![radiotext(40), tags.clone()]);
+
+    // Free format groups of another ODA stay on their own
+    let other = MessageElement::new(element_types::FREE_FORMAT, &[0x18, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    let mut batcher = FrameBatcher::new(0, 0).keep_text_tags(rt_plus_group);
+    batcher.push(radiotext(205));
+    batcher.push(radiotext(40));
+    batcher.push(other.clone());
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames[0].elements.len(), 2);
+    assert_eq!(frames[1].elements, vec![other]);
+
+    // No RT+ group, so no free format group is taken for RT+
+    let mut batcher = FrameBatcher::new(0, 0).keep_text_tags(None);
+    batcher.push(radiotext(205));
+    batcher.push(radiotext(40));
+    batcher.push(tags.clone());
+    let frames = batcher.into_frames(&mut SequenceCounter::new()).unwrap();
+    assert_eq!(frames[1].elements, vec![tags]);
+}
+
+#[test]
+fn test_batcher_errors() {
+    let mut batcher = FrameBatcher::new(0, 0);
+    batcher.push_group(vec![radiotext(200), radiotext(60)]);
+    assert_eq!(batcher.into_frames(&mut SequenceCounter::new()), Err(EncodeError::MessageTooLarge));
+
+    let mut batcher = FrameBatcher::new(1024, 0);
+    batcher.push(radiotext(10));
+    assert_eq!(batcher.into_frames(&mut SequenceCounter::new()), Err(EncodeError::InvalidSiteAddress));
+
+    assert_eq!(FrameBatcher::new(0, 0).into_frames(&mut SequenceCounter::new()), Ok(vec![]));
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "cli")]
+
+use std::io::{ BufRead, BufReader, Write };
+use std::net::TcpStream;
+use std::process::{ Child, Command, Output, Stdio };
+
+use uecp_rs::defs::{ ResponseCode, element_types };
+use uecp_rs::protocol::Frame;
+
+fn uecp(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uecp"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_encode() {
+    let output = uecp(&["encode", "--site", "62", "--encoder", "14", "--seq", "12", "--pi", "C201"], b"");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "FE 0F 8E 0C 05 01 00 00 C2 01 C7 65 FF\n");
+
+    let output = uecp(&["encode", "--ps", "TOO LONG PS"], b"");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_decode_hex_and_binary() {
+    let encoded = uecp(&["encode", "--seq", "3", "--pi", "C201", "--rt", "hello", "--format", "binary"], b"").stdout;
+
+    // Noise around and between frames is skipped
+    let mut input = vec![0x00, 0x12];
+    input.extend_from_slice(&encoded);
+    input.extend_from_slice(&encoded);
+    let output = uecp(&["decode"], &input);
+    assert!(output.status.success());
+    let expected = "SEQ=3 SITE=0 ENC=0 | PI dsn=0 psn=0 C201 | RT dsn=0 psn=0 AB=0 \"hello\"\n";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected.repeat(2));
+
+    let output = uecp(&["decode"], b"FE 00 00 03 05 01 00 00 C2 01 00 00 FF\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().ends_with(": CRC mismatch\n"));
+}
+
+#[test]
+fn test_decode_resyncs_after_cut_off_frame() {
+    let encoded = uecp(&["encode", "--seq", "3", "--pi", "C201"], b"").stdout;
+
+    // A frame cut off before its STP is dropped when the next STA comes
+    let mut input = b"FE 0F 8E 0C ".to_vec();
+    input.extend_from_slice(&encoded);
+    let output = uecp(&["decode"], &input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "SEQ=3 SITE=0 ENC=0 | PI dsn=0 psn=0 C201\n");
+}
+
+// `uecp listen` on a free local port, with the address it is bound to
+fn listen(args: &[&str]) -> (Child, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uecp"))
+        .args(["listen", "--tcp", "127.0.0.1:0"])
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(child.stderr.as_mut().unwrap()).read_line(&mut line).unwrap();
+    let address = line.trim().strip_prefix("listening on ").unwrap().to_string();
+    (child, address)
+}
+
+fn stop(mut child: Child) -> String {
+    child.kill().unwrap();
+    String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap()
+}
+
+#[test]
+fn test_send_and_listen() {
+    let (listener, address) = listen(&[]);
+
+    let output = uecp(&["send", "--tcp", &address, "--seq", "5", "--pi", "C201"], b"");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "> SEQ=5 SITE=0 ENC=0 | PI dsn=0 psn=0 C201\n< Ok\n"
+    );
+
+    // Every frame of the file is acknowledged in turn
+    let frames = [
+        uecp(&["encode", "--seq", "6", "--pty", "10"], b"").stdout,
+        uecp(&["encode", "--seq", "7", "--ps", "RADIO 1"], b"").stdout
+    ].concat();
+    let output = uecp(&["send", "--tcp", &address, "--from", "-"], &frames);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().matches("< Ok\n").count(), 2);
+
+    let log = stop(listener);
+    assert!(log.contains("SEQ=5 SITE=0 ENC=0 | PI dsn=0 psn=0 C201\n"));
+    assert!(log.contains("SEQ=7 SITE=0 ENC=0 | PS dsn=0 psn=0 \"RADIO 1 \"\n"));
+}
+
+#[test]
+fn test_send_without_ack() {
+    let (listener, address) = listen(&["--no-ack"]);
+
+    let output = uecp(&["send", "--tcp", &address, "--timeout", "200", "--pi", "C201"], b"");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "uecp: timed out waiting for UECP_ACK\n");
+
+    let output = uecp(&["send", "--tcp", &address, "--no-ack", "--pi", "C201"], b"");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "> SEQ=0 SITE=0 ENC=0 | PI dsn=0 psn=0 C201\n");
+
+    stop(listener);
+}
+
+#[test]
+fn test_listen_resyncs_and_acknowledges() {
+    let (listener, address) = listen(&[]);
+    let frame = uecp(&["encode", "--seq", "9", "--pi", "C201", "--format", "binary"], b"").stdout;
+
+    // Line noise and a cut-off frame come before the frame
+    let mut stream = TcpStream::connect(&address).unwrap();
+    stream.write_all(&[0x00, 0x12, 0xFE, 0x00, 0x00]).unwrap();
+    stream.write_all(&frame).unwrap();
+
+    let mut ack: Vec<u8> = vec![];
+    BufReader::new(&stream).read_until(0xFF, &mut ack).unwrap();
+    let ack = Frame::from_bytes(&ack).unwrap();
+    assert_eq!(ack.elements[0].element_type, element_types::UECP_ACK);
+    assert_eq!(ack.elements[0].data, vec![ResponseCode::Ok.as_u8()]);
+    drop(stream);
+
+    let log = stop(listener);
+    assert_eq!(log, "SEQ=9 SITE=0 ENC=0 | PI dsn=0 psn=0 C201\n");
+}
+
+#[test]
+fn test_listen_answers_damaged_frames() {
+    let (listener, address) = listen(&[]);
+    let frame = uecp(&["encode", "--seq", "9", "--site", "5", "--encoder", "3", "--pi", "C201", "--format", "binary"], b"").stdout;
+
+    // PI C200 doesn't match the CRC, and 0xFD 0x90 isn't a stuffed byte
+    let mut crc_error = frame.clone();
+    crc_error[9] = 0x00;
+    let mut bad_stuffing = frame.clone();
+    bad_stuffing[9] = 0xFD;
+
+    let mut stream = TcpStream::connect(&address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    for (bytes, code) in [(crc_error, ResponseCode::CRCError), (bad_stuffing, ResponseCode::BadStuffing)] {
+        stream.write_all(&bytes).unwrap();
+
+        let mut ack: Vec<u8> = vec![];
+        reader.read_until(0xFF, &mut ack).unwrap();
+        let ack = Frame::from_bytes(&ack).unwrap();
+        assert_eq!((ack.site_address, ack.encoder_address), (5, 3));
+        assert_eq!(ack.elements[0].element_type, element_types::UECP_ACK);
+        assert_eq!(ack.elements[0].data, vec![code.as_u8(), 9]);
+    }
+    drop(stream);
+
+    let log = stop(listener);
+    assert_eq!(log.lines().count(), 2);
+    assert!(log.ends_with("bad byte stuffing at offset 9\n"));
+}
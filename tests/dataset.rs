@@ -0,0 +1,89 @@
+use std::convert::TryFrom;
+use uecp_rs::dataset::*;
+use uecp_rs::defs::*;
+use uecp_rs::protocol::*;
+
+#[test]
+fn test_dataset_number() {
+    assert_eq!(DatasetNumber::from_u8(0), DatasetNumber::Current);
+    assert_eq!(DatasetNumber::from_u8(0xFE), DatasetNumber::AllExceptCurrent);
+    assert_eq!(DatasetNumber::from_u8(0xFF), DatasetNumber::All);
+    assert_eq!(DatasetNumber::from_u8(3), DatasetNumber::specific(3).unwrap());
+
+    assert_eq!(DatasetNumber::specific(0), Err(EncodeError::InvalidDatasetNumber));
+    assert_eq!(DatasetNumber::specific(0xFE), Err(EncodeError::InvalidDatasetNumber));
+    assert_eq!(DatasetNumber::specific(0xFF), Err(EncodeError::InvalidDatasetNumber));
+    assert_eq!(DatasetNumber::specific(0xFD).unwrap().as_u8(), 0xFD);
+    assert!(DatasetNumber::All.includes_current());
+    assert!(!DatasetNumber::AllExceptCurrent.includes_current());
+}
+
+#[test]
+fn test_make_psn_list() {
+    let mut dataset = Dataset::new(DatasetNumber::specific(2).unwrap());
+    dataset.add_service(1).unwrap();
+    dataset.add_service(4).unwrap();
+    assert_eq!(dataset.add_service(4), Err(EncodeError::DuplicateService(4)));
+
+    let element = dataset.make_psn_list().unwrap();
+    assert_eq!(element.into_bytes().unwrap(), vec![0x28, 0x02, 0x02, 0x01, 0x04]);
+    assert_eq!(Dataset::try_from(&element).unwrap(), dataset);
+}
+
+#[test]
+fn test_reserved_service_numbers() {
+    let mut dataset = Dataset::new(DatasetNumber::specific(1).unwrap());
+    assert_eq!(dataset.add_service(0), Err(EncodeError::InvalidServiceNumber(0)));
+    assert_eq!(dataset.add_service(0xFF), Err(EncodeError::InvalidServiceNumber(0xFF)));
+    dataset.add_service(0xFE).unwrap();
+    assert_eq!(dataset.make_psn_list().unwrap().data, vec![0xFE]);
+
+    let mut pi = MessageElement::new(element_types::PI, &[0xC2, 0x01]);
+    assert_eq!(dataset.target(&mut pi, 0), Err(EncodeError::InvalidServiceNumber(0)));
+    assert_eq!(dataset.toggle_service(0xFF, false), Err(EncodeError::InvalidServiceNumber(0xFF)));
+
+    assert_eq!(ServiceId::try_from(1).unwrap().get(), 1);
+    assert!(ServiceId::try_from(0).is_err());
+
+    // A PSN list naming a reserved PSN doesn't decode
+    let list = MessageElement::new(element_types::MAKE_PSN_LIST, &[0x01, 0x00]);
+    assert_eq!(Dataset::try_from(&list), Err(DecodeError::InvalidElementData(0x28)));
+}
+
+#[test]
+fn test_psn_toggle_and_select() {
+    let mut dataset = Dataset::new(DatasetNumber::specific(5).unwrap());
+    dataset.add_service(3).unwrap();
+
+    let toggle = dataset.toggle_service(3, false).unwrap();
+    assert_eq!(toggle.into_bytes().unwrap(), vec![0x0B, 0x05, 0x02, 0x03, 0x00]);
+    assert!(!dataset.service(3).unwrap().enabled);
+    assert_eq!(dataset.toggle_service(9, true), Err(EncodeError::UnknownService(9)));
+
+    let target = command_target(&toggle);
+    assert_eq!(target.dataset, Some(DatasetNumber::specific(5).unwrap()));
+    assert_eq!(target.program_service_number, Some(3));
+
+    let select = dataset.select().unwrap();
+    assert_eq!(select.into_bytes().unwrap(), vec![0x1C, 0x05]);
+    assert_eq!(command_target(&select).dataset, Some(DatasetNumber::specific(5).unwrap()));
+
+    assert_eq!(Dataset::new(DatasetNumber::All).select().err(), Some(EncodeError::InvalidDatasetNumber));
+}
+
+#[test]
+fn test_command_target() {
+    let mut dataset = Dataset::new(DatasetNumber::Current);
+    dataset.add_service(2).unwrap();
+
+    let mut pi = MessageElement::new(element_types::PI, &[0xC2, 0x01]);
+    dataset.target(&mut pi, 2).unwrap();
+    assert_eq!(dataset.target(&mut pi, 7), Err(EncodeError::UnknownService(7)));
+
+    let target = command_target(&pi);
+    assert_eq!(target.dataset, Some(DatasetNumber::Current));
+    assert_eq!(target.program_service_number, Some(2));
+
+    let rtc = MessageElement::new(element_types::CT_ON_OFF, &[0x01]);
+    assert_eq!(command_target(&rtc), CommandTarget { dataset: None, program_service_number: None });
+}
@@ -0,0 +1,125 @@
+use uecp_rs::defs::*;
+use uecp_rs::elements::radiotext::*;
+use uecp_rs::protocol::*;
+use std::convert::TryFrom;
+
+fn sample_frame() -> Frame {
+    let mut frame = Frame::with_sequence(12);
+    frame.set_addresses(62, 14);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    frame.elements.push(MessageElement::try_from(&RadioText::new("hello", false).unwrap()).unwrap());
+    frame
+}
+
+#[test]
+fn test_frame_summary() {
+    assert_eq!(
+        sample_frame().to_string(),
+        "SEQ=12 SITE=62 ENC=14 | PI dsn=0 psn=0 C201 | RT dsn=0 psn=0 AB=0 \"hello\""
+    );
+
+    let mut frame = Frame::new();
+    frame.elements.push(MessageElement::new(element_types::PTY, &[10]));
+    frame.elements.push(MessageElement::new(element_types::PS, b"RADIO 1 "));
+    frame.elements.push(MessageElement::new(element_types::RTC_CORRECTION, &[0x00, 0x05]));
+    assert_eq!(
+        frame.to_string(),
+        "SEQ=0 SITE=0 ENC=0 | PTY dsn=0 psn=0 PopMusic | PS dsn=0 psn=0 \"RADIO 1 \" | RTC_CORRECTION 0005"
+    );
+}
+
+#[test]
+fn test_frame_tree() {
+    let frame = sample_frame();
+    let tree = format!("{:#}", frame);
+
+    let lines: Vec<&str> = tree.lines().collect();
+    assert_eq!(lines[0], "SEQ=12 SITE=62 ENC=14");
+    assert_eq!(lines[1], "  [0]       STA   FE");
+    assert_eq!(lines[2], "  [1-2]     ADD   0F8E (site 62, encoder 14)");
+    assert_eq!(lines[5], "  [5-9]     PI dsn=0 psn=0 C201");
+    assert_eq!(lines[9], "    [8-9]     DATA  C2 01");
+    assert_eq!(lines[10], "      [8-9]     PI    C201 (country C, area 2, reference 01)");
+
+    assert_eq!(lines[11], "  [10-19]   RT dsn=0 psn=0 AB=0 \"hello\"");
+    assert_eq!(lines[17], "      [14]      CFG   00 (transmissions 0, A/B 0)");
+    assert_eq!(lines[18], "      [15-19]   TEXT  \"hello\"");
+    assert_eq!(lines.last(), Some(&"  [22]      STP   FF"));
+}
+
+#[test]
+fn test_tree_offsets_after_stuffing() {
+    let mut frame = Frame::with_sequence(1);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xFE, 0x01]));
+
+    let tree = format!("{:#}", frame);
+    // 0xFE is sent as 0xFD 0x01, so the PI data spans three bytes
+    assert!(tree.contains("    [8-10]    DATA  FE 01"));
+    assert!(tree.contains("STP   FF"));
+}
+
+#[test]
+fn test_element_tree() {
+    let element = MessageElement::new(element_types::RT, &[0x01, 0x48, 0x49]);
+    assert_eq!(element.to_string(), "RT dsn=0 psn=0 AB=1 \"HI\"");
+    assert_eq!(
+        format!("{:#}", element),
+        [
+            "[0-6]     RT dsn=0 psn=0 AB=1 \"HI\"",
+            "  [0]       MEC   0A (RT)",
+            "  [1]       DSN   00",
+            "  [2]       PSN   00",
+            "  [3]       MEL   03 (3 bytes)",
+            "  [4-6]     DATA  01 48 49",
+            "    [4]       CFG   01 (transmissions 0, A/B 1)",
+            "    [5-6]     TEXT  \"HI\""
+        ].join("\n")
+    );
+
+    // Invalid data is shown as hex along with the decoding error
+    let element = MessageElement::new(element_types::RT, &[]);
+    assert!(element.to_string().starts_with("RT dsn=0 psn=0 ("));
+    assert!(format!("{:#}", element).ends_with("  [4-]      DATA  "));
+}
+
+#[test]
+fn test_data_fields() {
+    let subfields = |element: MessageElement| -> Vec<String> {
+        format!("{:#}", element).lines()
+            .filter(|x| x.starts_with("    "))
+            .map(|x| x.trim().to_string())
+            .collect()
+    };
+
+    // RT+ tags sent as a free format 11A group: title at 0+6, artist at 9+5
+    let element = MessageElement::new(element_types::FREE_FORMAT, &[0x16, 0x08, 0x20, 0x0A, 0x21, 0x24]);
+    assert_eq!(subfields(element), vec![
+        "[1]       GROUP 16 (11A)",
+        "[2]       B2    08",
+        "[3-4]     B3    200A",
+        "[5-6]     B4    2124",
+        "[2-6]     RT+   toggle 0, running 1, type 1 start 0 length 6, type 4 start 9 length 5"
+    ]);
+
+    // The same blocks in a 12A group are some other ODA
+    let element = MessageElement::new(element_types::FREE_FORMAT, &[0x18, 0x08, 0x20, 0x0A, 0x21, 0x24]);
+    assert_eq!(subfields(element), vec![
+        "[1]       GROUP 18 (12A)",
+        "[2]       B2    08",
+        "[3-4]     B3    200A",
+        "[5-6]     B4    2124"
+    ]);
+
+    let element = MessageElement::new(element_types::DAB_DL_COMMAND, &[0x05, 0x01, 0x00, 0x05, 0x04, 0x07, 0x04]);
+    assert_eq!(subfields(element), vec![
+        "[2]       CMD   05 (toggle 0, running 1, 2 tags)",
+        "[3-5]     TAG   010005 (type 1, start 0, length 6)",
+        "[6-8]     TAG   040704 (type 4, start 7, length 5)"
+    ]);
+
+    let element = MessageElement::new(element_types::DAB_DL_MESSAGE, &[0x0F, 0x48, 0x69]);
+    assert_eq!(subfields(element), vec!["[2]       CHSET 0F (Utf8)", "[3-4]     TEXT  \"Hi\""]);
+
+    let element = MessageElement::new(element_types::PIN, &[0x89, 0x1E]);
+    assert_eq!(subfields(element), vec!["[3-4]     PIN   day 17 04:30"]);
+}
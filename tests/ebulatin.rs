@@ -15,3 +15,18 @@ fn test_to_e1_special_chars() {
 
     assert_eq!(result, vec![0x82, 0x81, 0x9B, 0x99, 0x97, 0x23, 0xAB, 0xAA]);
 }
+
+#[test]
+fn test_from_e1() {
+    let result = uecp_rs::ebulatin::from_e1(&[0x82, 0x81, 0x9B, 0x74, 0x65, 0x73, 0x74, 0xAB]);
+
+    assert_eq!(result, "éàçtest$");
+}
+
+#[test]
+fn test_decode_table_matches_encode_table() {
+    for b in 0x20..=0xFEu8 {
+        let c = uecp_rs::ebulatin::decode_byte(b);
+        assert_eq!(uecp_rs::ebulatin::encode_char(c), b, "{:02X} decodes to {:?}", b, c);
+    }
+}
@@ -0,0 +1,491 @@
+use std::convert::TryFrom;
+use uecp_rs::defs::*;
+use uecp_rs::protocol::*;
+use uecp_rs::elements::TypedElement;
+use uecp_rs::elements::dab::*;
+use uecp_rs::elements::encoder_config::*;
+use uecp_rs::elements::ews::*;
+use uecp_rs::elements::flags::*;
+use uecp_rs::elements::free_format::*;
+use uecp_rs::elements::group_sequence::*;
+use uecp_rs::elements::in_house::*;
+use uecp_rs::elements::linkage::*;
+use uecp_rs::elements::pin::*;
+use uecp_rs::elements::radiotext::*;
+use uecp_rs::elements::signal::*;
+use uecp_rs::elements::slow_labeling::*;
+use uecp_rs::elements::tags::*;
+use uecp_rs::elements::tdc::*;
+
+#[test]
+fn test_ecc_from_country() {
+    assert_eq!(ecc_from_country("DE", 0xD313), Some(0xE0));
+    assert_eq!(ecc_from_country("fr", 0xF201), Some(0xE1));
+    assert_eq!(ecc_from_country("FR", 0xD313), None);
+    assert_eq!(ecc_from_country("XX", 0xF201), None);
+}
+
+#[test]
+fn test_language_code_from_iso639() {
+    assert_eq!(language_code_from_iso639("fr"), Some(0x0F));
+    assert_eq!(language_code_from_iso639("EN"), Some(0x09));
+    assert_eq!(language_code_from_iso639("xx"), None);
+}
+
+#[test]
+fn test_slow_labeling_ecc_encode_and_decode() {
+    let source = SlowLabeling::extended_country_code("FR", 0xF201).unwrap();
+    let element = MessageElement::try_from(source).unwrap();
+    assert_eq!(element.element_type, element_types::SLOW_LABELING);
+    assert_eq!(element.data, &[0x00, 0xE1]);
+
+    let decoded = SlowLabeling::try_from(&element).unwrap();
+    assert_eq!(decoded, source);
+}
+
+#[test]
+fn test_slow_labeling_language_encode() {
+    let element = MessageElement::try_from(SlowLabeling::language("de").unwrap()).unwrap();
+    assert_eq!(element.data, &[0x30, 0x08]);
+}
+
+#[test]
+fn test_slow_labeling_out_of_range() {
+    let result = MessageElement::try_from(SlowLabeling::TmcIdentification(0x1000));
+    assert_eq!(result.err(), Some(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_slow_labeling_wrong_element_type() {
+    let element = MessageElement::new(element_types::PI, &[0xC2, 0x01]);
+    assert_eq!(SlowLabeling::try_from(&element), Err(DecodeError::UnexpectedElementType(0x01)));
+}
+
+#[test]
+fn test_decoder_identification_encode_and_decode() {
+    let source = DecoderIdentification { stereo: true, dynamic_pty: true, ..Default::default() };
+    let element = MessageElement::from(source);
+    assert_eq!(element.data, &[0x09]);
+    assert_eq!(DecoderIdentification::try_from(&element).unwrap(), source);
+
+    let invalid = MessageElement::new(element_types::DI, &[0x10]);
+    assert_eq!(DecoderIdentification::try_from(&invalid), Err(DecodeError::InvalidElementData(0x04)));
+}
+
+#[test]
+fn test_music_speech_encode_and_decode() {
+    let element = MessageElement::from(MusicSpeech::Music);
+    assert_eq!(element.element_type, element_types::MS);
+    assert_eq!(element.data, &[0x01]);
+    assert_eq!(MusicSpeech::try_from(&element).unwrap(), MusicSpeech::Music);
+}
+
+#[test]
+fn test_traffic_flags_encode_and_decode() {
+    let flags = TrafficFlags::new(true, false);
+    assert_eq!(flags, TrafficFlags::TrafficProgramme);
+    assert!(flags.tp());
+    assert!(!flags.ta());
+
+    let element = MessageElement::from(TrafficFlags::TrafficAnnouncement);
+    assert_eq!(element.data, &[0x03]);
+    assert_eq!(TrafficFlags::try_from(&element).unwrap(), TrafficFlags::TrafficAnnouncement);
+}
+
+#[test]
+fn test_pin_encode_and_decode() {
+    let pin = Pin::new(17, 20, 30).unwrap();
+    let element = MessageElement::from(pin);
+    assert_eq!(element.element_type, element_types::PIN);
+    assert_eq!(element.data, &[0x8D, 0x1E]);
+    assert_eq!(Pin::try_from(&element).unwrap(), pin);
+}
+
+#[test]
+fn test_pin_invalid_values() {
+    assert_eq!(Pin::new(0, 12, 0), Err(EncodeError::ParameterOutOfRange));
+    assert_eq!(Pin::new(32, 12, 0), Err(EncodeError::ParameterOutOfRange));
+    assert_eq!(Pin::new(1, 24, 0), Err(EncodeError::ParameterOutOfRange));
+    assert_eq!(Pin::new(1, 12, 60), Err(EncodeError::ParameterOutOfRange));
+
+    // Hour 24
+    let element = MessageElement::new(element_types::PIN, &[0x0E, 0x00]);
+    assert_eq!(Pin::try_from(&element), Err(DecodeError::InvalidElementData(0x06)));
+}
+
+#[test]
+fn test_pin_none() {
+    let element = MessageElement::from(Pin::NONE);
+    assert_eq!(element.data, &[0x00, 0x00]);
+
+    // Day 0 is sent on air when there is no PIN
+    let element = MessageElement::new(element_types::PIN, &[0x03, 0x1E]);
+    let pin = Pin::try_from(&element).unwrap();
+    assert!(pin.is_none());
+    assert_eq!(pin, Pin::NONE);
+}
+
+#[test]
+fn test_pin_from_system_time() {
+    // 2024-02-29 23:45 UTC
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_709_250_300);
+    assert_eq!(Pin::from_system_time(time, 0).unwrap(), Pin::new(29, 23, 45).unwrap());
+    // 2024-03-01 00:45 at UTC+1
+    assert_eq!(Pin::from_system_time(time, 60).unwrap(), Pin::new(1, 0, 45).unwrap());
+}
+
+#[test]
+fn test_pin_for_eon_service() {
+    let mut element = MessageElement::from(Pin::new(1, 8, 0).unwrap());
+    element.set_dsn_psn(1, 3);
+
+    let encoded = element.into_bytes().unwrap();
+    assert_eq!(encoded, vec![0x06, 0x01, 0x03, 0x0A, 0x00]);
+}
+
+#[test]
+fn test_linkage_info_encode_and_decode() {
+    let mut info = LinkageInfo::new_international(0xF, 0x12).unwrap();
+    info.linkage_actuator = true;
+
+    let element = MessageElement::try_from(info).unwrap();
+    assert_eq!(element.element_type, element_types::LINKAGE_INFO);
+    assert_eq!(element.data, &[0xAF, 0x12]);
+    assert_eq!(LinkageInfo::try_from(&element).unwrap(), info);
+
+    assert_eq!(LinkageInfo::new(0x1000), Err(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_link_services() {
+    let set = LinkageInfo::new(0x123).unwrap();
+    let elements = link_services(set, true, 1, &[0, 2, 5]).unwrap();
+
+    assert_eq!(elements.len(), 3);
+    for (element, psn) in elements.iter().zip(&[0, 2, 5]) {
+        assert_eq!(element.dataset_number, 1);
+        assert_eq!(element.program_service_number, *psn);
+        assert_eq!(element.data, &[0x81, 0x23]);
+    }
+}
+
+#[test]
+fn test_ews_message_encode_and_decode() {
+    let message = EwsMessage::new(0x15, 0x1234, 0xABCD).unwrap();
+    let element = MessageElement::try_from(message).unwrap();
+    assert_eq!(element.element_type, element_types::EWS);
+    assert_eq!(element.data, &[0x15, 0x12, 0x34, 0xAB, 0xCD]);
+    assert_eq!(EwsMessage::try_from(&element).unwrap(), message);
+
+    assert_eq!(EwsMessage::new(0x20, 0, 0), Err(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_ews_alert_activation_and_revert() {
+    let message = EwsMessage::new(0x01, 0x0002, 0x0003).unwrap();
+    let mut alert = EwsAlert::new(message, PTY::PopMusic, TrafficFlags::TrafficProgramme, std::time::Duration::from_secs(60));
+    alert.channel = Some(0x123);
+    alert.program_service_number = 1;
+
+    let now = std::time::Instant::now();
+    let (elements, mut revert) = alert.start(now).unwrap();
+    let codes: Vec<u8> = elements.iter().map(|x| x.element_type.code).collect();
+    assert_eq!(codes, vec![0x1A, 0x2B, 0x07, 0x03]);
+    assert_eq!(PTY::try_from(&elements[2]).unwrap(), PTY::Alarm);
+    assert_eq!(elements[2].program_service_number, 1);
+    assert_eq!(TrafficFlags::try_from(&elements[3]).unwrap(), TrafficFlags::TrafficAnnouncement);
+
+    assert!(revert.poll(now + std::time::Duration::from_secs(30)).is_none());
+    let reverted = revert.poll(now + std::time::Duration::from_secs(60)).unwrap();
+    assert_eq!(PTY::try_from(&reverted[0]).unwrap(), PTY::PopMusic);
+    assert_eq!(TrafficFlags::try_from(&reverted[1]).unwrap(), TrafficFlags::TrafficProgramme);
+    assert!(!revert.is_pending());
+    assert!(revert.poll(now + std::time::Duration::from_secs(90)).is_none());
+}
+
+#[test]
+fn test_in_house_encode_and_decode() {
+    let source = InHouse::new_a(0x05, 0x12345678).unwrap();
+    let element = MessageElement::try_from(source).unwrap();
+    assert_eq!(element.element_type, element_types::IH);
+    assert_eq!(element.data, &[0x00, 0x05, 0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(InHouse::try_from(&element).unwrap(), source);
+
+    let source = InHouse::new_b(0x1F, 0xBEEF).unwrap();
+    let element = MessageElement::try_from(source).unwrap();
+    assert_eq!(element.data, &[0x01, 0x1F, 0x00, 0x00, 0xBE, 0xEF]);
+    assert_eq!(InHouse::try_from(&element).unwrap().payload(), 0xBEEF);
+
+    assert_eq!(InHouse::new_a(0x20, 0), Err(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_free_format_encode_and_decode() {
+    let group_type = GroupType::new(11, GroupVersion::A).unwrap();
+    let source = FreeFormat::new(group_type, 0x03, 0x1234, 0x5678).unwrap();
+    let element = MessageElement::try_from(source).unwrap();
+    assert_eq!(element.element_type, element_types::FREE_FORMAT);
+    assert_eq!(element.data, &[0x16, 0x03, 0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(FreeFormat::try_from(&element).unwrap(), source);
+}
+
+#[test]
+fn test_tdc_groups() {
+    let tdc = TransparentData::new(3, &[1, 2, 3, 4, 5]).unwrap();
+    assert_eq!(tdc.groups(GroupVersion::A), vec![vec![1, 2, 3, 4], vec![5, 0, 0, 0]]);
+    assert_eq!(tdc.groups(GroupVersion::B), vec![vec![1, 2], vec![3, 4], vec![5, 0]]);
+
+    // Only the groups are padded, the element keeps the payload as it is
+    let elements = tdc.into_elements().unwrap();
+    assert_eq!(elements.len(), 1);
+    assert_eq!(elements[0].data, &[3, 1, 2, 3, 4, 5]);
+    assert_eq!(TransparentData::try_from(&elements[0]).unwrap(), tdc);
+    assert_eq!(TypedElement::TransparentData(tdc.clone()).into_element().unwrap(), elements[0]);
+
+    // The typed element goes through the same checks
+    let invalid = TransparentData { channel: 32, data: vec![1] };
+    assert_eq!(TypedElement::TransparentData(invalid).into_element(), Err(EncodeError::ParameterOutOfRange));
+    let long = TransparentData::new(3, &[0; 253]).unwrap();
+    assert_eq!(long.into_element(), Err(EncodeError::ElementTooLarge));
+}
+
+#[test]
+fn test_tdc_chunking() {
+    let payload: Vec<u8> = (0..=255).cycle().take(600).collect();
+    let tdc = TransparentData::new(31, &payload).unwrap();
+    let elements = tdc.into_elements().unwrap();
+
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements[0].data.len(), 253);
+    assert_eq!(elements[2].data.len(), 1 + 96);
+    assert!(elements.iter().all(|x| x.into_bytes().is_ok()));
+
+    let joined: Vec<u8> = elements.iter().flat_map(|x| x.data[1..].to_vec()).collect();
+    assert_eq!(joined, payload);
+
+    assert!(TransparentData::new(32, &[]).is_err());
+}
+
+#[test]
+fn test_group_sequence_parse_encode_and_decode() {
+    let sequence = GroupSequence::parse("0A, 2A, 0A, 3A, 11A").unwrap();
+    assert_eq!(sequence.groups.len(), 5);
+    assert_eq!(sequence.groups[4], GroupType::new(11, GroupVersion::A).unwrap());
+
+    let element = sequence.into_element().unwrap();
+    assert_eq!(element.element_type, element_types::GROUP_SEQUENCE);
+    assert_eq!(element.data, &[0x00, 0x04, 0x00, 0x06, 0x16]);
+    assert_eq!(GroupSequence::try_from(&element).unwrap(), sequence);
+
+    let extended = sequence.into_extended_element().unwrap();
+    assert_eq!(extended.element_type, element_types::EXTENDED_GROUP_SEQUENCE);
+    assert_eq!(GroupSequence::try_from(&extended).unwrap(), sequence);
+
+    let error = GroupSequence::parse("0A, 16A").unwrap_err();
+    assert_eq!((error.index, error.token.as_str(), error.error), (1, "16A", GroupTypeError::InvalidNumber));
+    assert_eq!(error.to_string(), "group 2 (\"16A\"): group number must be 0 to 15");
+    assert_eq!(GroupSequence::parse("0A 2C").unwrap_err().error, GroupTypeError::InvalidVersion);
+    assert_eq!(GroupSequence::parse("0A,A").unwrap_err().error, GroupTypeError::MissingNumber);
+}
+
+#[test]
+fn test_variant_code_sequence_encode_and_decode() {
+    let sequence = VariantCodeSequence::new(GroupType::new(1, GroupVersion::A).unwrap(), &[0, 3, 0, 7]);
+    let element = MessageElement::try_from(&sequence).unwrap();
+    assert_eq!(element.element_type, element_types::GROUP_VARIANT_CODE_SEQUENCE);
+    assert_eq!(element.data, &[0x02, 0, 3, 0, 7]);
+    assert_eq!(VariantCodeSequence::try_from(&element).unwrap(), sequence);
+
+    // Variant codes are 4 bits, whether encoding or decoding
+    let invalid = VariantCodeSequence::new(GroupType::new(1, GroupVersion::A).unwrap(), &[0, 16]);
+    assert_eq!(MessageElement::try_from(&invalid), Err(EncodeError::ParameterOutOfRange));
+    let element = MessageElement::new(element_types::GROUP_VARIANT_CODE_SEQUENCE, &[0x02, 0, 16]);
+    assert_eq!(VariantCodeSequence::try_from(&element), Err(DecodeError::InvalidElementData(0x29)));
+}
+
+#[test]
+fn test_group_sequence_bandwidth() {
+    let sequence = GroupSequence::parse("0A 2A 0A 2A 0A 3A 0A 2A").unwrap();
+    let report = sequence.bandwidth();
+
+    assert_eq!(report.groups_per_second.len(), 3);
+    assert!((report.rate_of(0, None) - GROUPS_PER_SECOND / 2.0).abs() < 1e-9);
+    assert!((report.rate_of(2, Some(GroupVersion::A)) - GROUPS_PER_SECOND * 3.0 / 8.0).abs() < 1e-9);
+
+    let ps_time = report.ps_time.unwrap().as_secs_f64();
+    assert!((ps_time - 4.0 / (GROUPS_PER_SECOND / 2.0)).abs() < 1e-6);
+
+    // 5 AFs plus the count code fit in 3 groups
+    let af_time = report.af_cycle_time(5).unwrap().as_secs_f64();
+    assert!((af_time - 3.0 / (GROUPS_PER_SECOND / 2.0)).abs() < 1e-6);
+
+    assert!(GroupSequence::parse("0A 3A").unwrap().bandwidth().rt_time.is_none());
+    assert_eq!(group_service(GroupType::new(2, GroupVersion::A).unwrap()), "RadioText");
+}
+
+#[test]
+fn test_site_and_encoder_address_commands() {
+    let element = MessageElement::try_from(SiteAddressCommand::add(1023).unwrap()).unwrap();
+    assert_eq!(element.element_type, element_types::SITE_ADDRESS);
+    assert_eq!(element.data, &[0x01, 0x03, 0xFF]);
+    assert_eq!(SiteAddressCommand::try_from(&element).unwrap(), SiteAddressCommand::add(1023).unwrap());
+    assert_eq!(SiteAddressCommand::remove(1024), Err(EncodeError::InvalidSiteAddress));
+
+    let element = MessageElement::try_from(EncoderAddressCommand::clear_all()).unwrap();
+    assert_eq!(element.data, &[0x02, 0x00]);
+    assert_eq!(EncoderAddressCommand::add(64), Err(EncodeError::InvalidEncoderAddress));
+
+    let invalid = MessageElement::new(element_types::ENCODER_ADDRESS, &[0x01, 0x40]);
+    assert_eq!(EncoderAddressCommand::try_from(&invalid), Err(DecodeError::InvalidElementData(0x27)));
+}
+
+#[test]
+fn test_comm_settings() {
+    let element = MessageElement::from(CommMode::BidirectionalSpontaneous);
+    assert_eq!(element.data, &[0x02]);
+    assert_eq!(CommMode::try_from(&element).unwrap(), CommMode::BidirectionalSpontaneous);
+
+    let speed = CommPortSpeed { port: 1, speed: BaudRate::from_bits_per_second(9600).unwrap() };
+    let element = MessageElement::from(speed);
+    assert_eq!(element.data, &[0x01, 0x05]);
+    assert_eq!(CommPortSpeed::try_from(&element).unwrap(), speed);
+    assert!(BaudRate::from_bits_per_second(14400).is_none());
+
+    let element = MessageElement::from(CommPortMode { port: 2, enabled: true });
+    assert_eq!(CommPortMode::try_from(&element).unwrap(), CommPortMode { port: 2, enabled: true });
+
+    let element = MessageElement::from(CommPortTimeout { port: 2, minutes: 10 });
+    assert_eq!(element.data, &[0x02, 0x0A]);
+}
+
+#[test]
+fn test_encoder_access_right() {
+    let access = EncoderAccessRight { port: 1, dataset_number: 0xFF, rights: AccessRights::READ };
+    let element = MessageElement::try_from(access).unwrap();
+    assert_eq!(element.data, &[0x01, 0xFF, 0x01]);
+
+    let decoded = EncoderAccessRight::try_from(&element).unwrap();
+    assert!(decoded.rights.contains(AccessRights::READ));
+    assert!(!decoded.rights.contains(AccessRights::WRITE));
+
+    let invalid = EncoderAccessRight { rights: AccessRights(0x80), ..access };
+    assert_eq!(MessageElement::try_from(invalid).err(), Some(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_rds_phase_and_level() {
+    let phase = RdsPhase::from_degrees(2, 123.4).unwrap();
+    let element = MessageElement::from(phase);
+    assert_eq!(element.element_type, element_types::RDS_PHASE);
+    assert_eq!(element.data, &[0x44, 0xD2]);
+    assert_eq!(RdsPhase::try_from(&element).unwrap().degrees(), 123.4);
+    assert_eq!(RdsPhase::new(0, 3600), Err(EncodeError::ParameterOutOfRange));
+    assert_eq!(RdsPhase::from_degrees(0, -90.0).unwrap().degrees(), 270.0);
+
+    let level = RdsLevel::new(1, 2500).unwrap();
+    let element = MessageElement::from(level);
+    assert_eq!(element.data, &[0x29, 0xC4]);
+    assert_eq!(RdsLevel::try_from(&element).unwrap(), level);
+    assert_eq!(RdsLevel::new(8, 0), Err(EncodeError::ParameterOutOfRange));
+}
+
+#[test]
+fn test_signal_settings() {
+    let mut on_off = RdsOnOff::default();
+    on_off.set(1, true).unwrap();
+    assert!(on_off.set(8, true).is_err());
+
+    let mut settings = SignalSettings::default();
+    assert_eq!(settings.output_enabled(), None);
+
+    assert!(settings.apply(&MessageElement::from(on_off)).unwrap());
+    assert_eq!(settings.output_enabled(), Some(false));
+
+    settings.apply(&MessageElement::from(ReferenceInput::new(1).unwrap())).unwrap();
+    settings.apply(&MessageElement::from(RdsLevel::new(1, 3000).unwrap())).unwrap();
+    settings.apply(&MessageElement::from(RdsPhase::new(0, 900).unwrap())).unwrap();
+    assert_eq!(settings.output_enabled(), Some(true));
+    assert_eq!(settings.level().unwrap().millivolts(), 3000);
+    assert_eq!(settings.phase(), None);
+
+    assert!(!settings.apply(&MessageElement::new(element_types::PI, &[0xC2, 0x01])).unwrap());
+}
+
+#[test]
+fn test_radiotext_encode_and_decode() {
+    let source = RadioText::new("hello", true).unwrap();
+    let element = MessageElement::try_from(&source).unwrap();
+    assert_eq!(element.element_type, element_types::RT);
+    assert_eq!(element.data, &[0x01, 0x68, 0x65, 0x6C, 0x6C, 0x6F]);
+    assert_eq!(RadioText::try_from(&element).unwrap(), source);
+
+    let too_long = "x".repeat(65);
+    assert_eq!(RadioText::new(&too_long, false), Err(EncodeError::TextTooLong));
+}
+
+#[test]
+fn test_rt_plus_tags() {
+    let text = TaggedText::new()
+        .tagged(ContentType::ITEM_ARTIST, "Daft Punk")
+        .plain(" - ")
+        .tagged(ContentType::ITEM_TITLE, "One More Time");
+    assert_eq!(text.text, "Daft Punk - One More Time");
+    assert_eq!(text.tags[1], Tag { content_type: ContentType::ITEM_TITLE, start: 12, length: 13 });
+
+    let group_type = GroupType::new(11, GroupVersion::A).unwrap();
+    let elements = text.radiotext_elements(false, Some(group_type)).unwrap();
+    assert_eq!(elements.len(), 2);
+    assert_eq!(elements[1].element_type, element_types::FREE_FORMAT);
+
+    let free_format = FreeFormat::try_from(&elements[1]).unwrap();
+    assert_eq!(free_format.group_type, group_type);
+    assert_eq!(free_format.block_2, 0x08);
+    assert_eq!(free_format.block_3, 0x8010);
+    assert_eq!(free_format.block_4, 0x098C);
+
+    let decoded = RtPlusTags::from_free_format(&free_format);
+    assert!(decoded.item_running);
+    assert_eq!(decoded.tags, text.tags);
+}
+
+#[test]
+fn test_dynamic_label_encode_and_decode() {
+    let label = DynamicLabel::new("Café", DabCharset::Utf8).unwrap();
+    let element = MessageElement::try_from(&label).unwrap();
+    assert_eq!(element.element_type, element_types::DAB_DL_MESSAGE);
+    assert_eq!(element.data, &[0x0F, 0x43, 0x61, 0x66, 0xC3, 0xA9]);
+    assert_eq!(DynamicLabel::try_from(&element).unwrap(), label);
+
+    let label = DynamicLabel::new("Café", DabCharset::Ucs2).unwrap();
+    let element = MessageElement::try_from(&label).unwrap();
+    assert_eq!(element.data.len(), 9);
+    assert_eq!(DynamicLabel::try_from(&element).unwrap().text, "Café");
+
+    let label = DynamicLabel::new("Café", DabCharset::EbuLatin).unwrap();
+    let element = MessageElement::try_from(&label).unwrap();
+    assert_eq!(element.data, &[0x00, 0x43, 0x61, 0x66, 0x82]);
+
+    let too_long = "x".repeat(129);
+    assert_eq!(DynamicLabel::new(&too_long, DabCharset::Utf8), Err(EncodeError::TextTooLong));
+}
+
+#[test]
+fn test_dl_plus_tags() {
+    let text = TaggedText::new()
+        .tagged(ContentType::ITEM_ARTIST, "Artist")
+        .plain(" / ")
+        .tagged(ContentType::ITEM_TITLE, "Title");
+
+    let elements = text.dynamic_label_elements(DabCharset::Utf8).unwrap();
+    assert_eq!(elements[1].element_type, element_types::DAB_DL_COMMAND);
+    assert_eq!(elements[1].data, &[0x05, 0x04, 0x00, 0x05, 0x01, 0x09, 0x04]);
+
+    let decoded = DlPlusTags::try_from(&elements[1]).unwrap();
+    assert!(!decoded.item_toggle);
+    assert!(decoded.item_running);
+    assert_eq!(decoded.tags, text.tags);
+
+    let empty = MessageElement::try_from(&DlPlusTags::new(false, false, &[]).unwrap()).unwrap();
+    assert_eq!(empty.data, &[0x00, 0x00, 0x00, 0x00]);
+    assert!(DlPlusTags::try_from(&empty).unwrap().tags.is_empty());
+}
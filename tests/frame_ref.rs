@@ -0,0 +1,63 @@
+use std::convert::TryFrom;
+use uecp_rs::defs::*;
+use uecp_rs::frame_ref::*;
+use uecp_rs::protocol::*;
+
+fn sample_frame() -> Frame {
+    let mut frame = Frame::with_sequence(24);
+    frame.set_addresses(62, 14);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    frame.elements.push(MessageElement::new(element_types::RT, &[0x00, 0x48, 0xFE, 0x49]));
+    frame.elements.push(MessageElement::new(element_types::SLOW_LABELING, &[0x00, 0xE0]));
+    frame.elements[2].set_dsn_psn(3, 0);
+    frame
+}
+
+#[test]
+fn test_frame_ref_parse() {
+    let source = sample_frame();
+    let bytes = source.into_bytes().unwrap();
+
+    let mut scratch = [0u8; 64];
+    let frame = FrameRef::parse(&bytes, &mut scratch).unwrap();
+    assert_eq!(frame.sequence_counter, 24);
+    assert_eq!(frame.site_address, 62);
+    assert_eq!(frame.encoder_address, 14);
+
+    let elements: Vec<ElementRef> = frame.elements().collect::<Result<_, _>>().unwrap();
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements[0].element_type, element_types::PI);
+    assert_eq!(elements[0].data, &[0xC2, 0x01]);
+    assert_eq!(elements[1].data, &[0x00, 0x48, 0xFE, 0x49]);
+    assert_eq!(elements[2].dataset_number, 3);
+    assert_eq!(elements[2].data, &[0x00, 0xE0]);
+
+    assert_eq!(Frame::try_from(frame).unwrap(), source);
+}
+
+#[test]
+fn test_frame_ref_errors() {
+    let bytes = sample_frame().into_bytes().unwrap();
+
+    let mut scratch = [0u8; 8];
+    assert_eq!(FrameRef::parse(&bytes, &mut scratch), Err(DecodeError::BufferTooSmall));
+
+    let mut scratch = [0u8; 64];
+    let mut corrupted = bytes.clone();
+    corrupted[5] ^= 0x01;
+    assert_eq!(FrameRef::parse(&corrupted, &mut scratch), Err(DecodeError::CRCError));
+
+    assert_eq!(FrameRef::parse(&[0xFE, 0x00, 0xFF], &mut scratch), Err(DecodeError::FrameHeaderTruncated));
+    assert_eq!(FrameRef::parse(&[], &mut scratch), Err(DecodeError::FrameHeaderTruncated));
+}
+
+#[test]
+fn test_element_iter_stops_on_error() {
+    // PI element, then an unknown element code
+    let message = [0x01, 0x00, 0x00, 0xC2, 0x01, 0xF0, 0x00];
+    let mut elements = ElementIter::new(&message);
+
+    assert_eq!(elements.next().unwrap().unwrap().data, &[0xC2, 0x01]);
+    assert_eq!(elements.next(), Some(Err(DecodeError::UnknownElementType(0xF0))));
+    assert_eq!(elements.next(), None);
+}
@@ -0,0 +1,84 @@
+use uecp_rs::defs::*;
+use uecp_rs::ebulatin::*;
+use uecp_rs::framing::*;
+use uecp_rs::protocol::*;
+
+#[test]
+fn test_encode_element_into() {
+    let mut buffer = [0u8; MAX_ELEMENT_LENGTH];
+
+    let length = encode_element_into(element_types::PI, 1, 2, &[0xC2, 0x01], &mut buffer).unwrap();
+    assert_eq!(&buffer[..length], &[0x01, 0x01, 0x02, 0xC2, 0x01]);
+
+    let length = encode_element_into(element_types::RT, 0, 0, &[0x00, 0x41, 0x42], &mut buffer).unwrap();
+    assert_eq!(&buffer[..length], &[0x0A, 0x00, 0x00, 0x03, 0x00, 0x41, 0x42]);
+
+    let mut small = [0u8; 4];
+    assert_eq!(encode_element_into(element_types::PI, 0, 0, &[0xC2, 0x01], &mut small), Err(EncodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_encode_frame_into() {
+    let mut frame = Frame::with_sequence(13);
+    frame.set_addresses(341, 21);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xFE, 0xFD]));
+
+    let message = frame.elements[0].into_bytes().unwrap();
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+    let length = encode_frame_into(341, 21, 13, &message, &mut buffer).unwrap();
+    assert_eq!(&buffer[..length], frame.into_bytes().unwrap().as_slice());
+
+    let mut small = [0u8; 8];
+    assert_eq!(encode_frame_into(341, 21, 13, &message, &mut small), Err(EncodeError::BufferTooSmall));
+    assert_eq!(encode_frame_into(1024, 21, 13, &message, &mut buffer), Err(EncodeError::InvalidSiteAddress));
+}
+
+#[test]
+fn test_stuffing_into() {
+    let source = [0x12, 0xFD, 0x15, 0xFF, 0xAB, 0xFE, 0x26];
+    let mut stuffed = [0u8; 14];
+    let length = stuff_into(&source, &mut stuffed).unwrap();
+    assert_eq!(&stuffed[..length], &[0x12, 0xFD, 0x00, 0x15, 0xFD, 0x02, 0xAB, 0xFD, 0x01, 0x26]);
+
+    let mut unstuffed = [0u8; 7];
+    assert_eq!(unstuff_into(&stuffed[..length], &mut unstuffed), Ok(7));
+    assert_eq!(unstuffed, source);
+
+    assert_eq!(unstuff_into(&stuffed[..length], &mut [0u8; 6]), Err(DecodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_to_e1_into() {
+    let mut buffer = [0u8; 8];
+    let length = to_e1_into("Café", &mut buffer).unwrap();
+    assert_eq!(&buffer[..length], &[0x43, 0x61, 0x66, 0x82]);
+    assert_eq!(decode_byte(0x82), 'é');
+
+    assert_eq!(to_e1_into("Radio FM 1", &mut buffer), Err(EncodeError::BufferTooSmall));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_encode_frame_heapless() {
+    let message = [0x07, 0x00, 0x00, 0x0A];
+    let frame = encode_frame(0, 0, 1, &message).unwrap();
+
+    let mut buffer = [0u8; MAX_FRAME_LENGTH];
+    let length = encode_frame_into(0, 0, 1, &message, &mut buffer).unwrap();
+    assert_eq!(frame.as_slice(), &buffer[..length]);
+}
+
+#[test]
+fn test_crc16() {
+    // CRC-16/GENIBUS check value
+    assert_eq!(compute_crc16_genibus(b"123456789"), 0xD64E);
+    assert_eq!(compute_crc16_genibus(&[]), 0x0000);
+
+    let data = "2D111234010105ABCD123F0XXXX11069212491000320066".as_bytes();
+    let mut crc = Crc16::new();
+    for chunk in data.chunks(5) {
+        crc.update(chunk);
+    }
+    assert_eq!(crc.finalize(), 0x9723);
+    assert_eq!(crc.finalize(), compute_crc16_genibus(data));
+}
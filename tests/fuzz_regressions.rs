@@ -0,0 +1,64 @@
+// Inputs that used to panic, kept from the fuzzing corpus
+use uecp_rs::defs::*;
+use uecp_rs::framing::*;
+use uecp_rs::protocol::*;
+
+// Wraps ADD, SEQ, MEL and message bytes into a frame with a valid CRC
+fn frame_with_crc(content: &[u8]) -> Vec<u8> {
+    let mut unstuffed = content.to_vec();
+    unstuffed.extend_from_slice(&compute_crc16_genibus(content).to_be_bytes());
+
+    let mut stuffed = vec![0u8; unstuffed.len() * 2];
+    let length = stuff_into(&unstuffed, &mut stuffed).unwrap();
+
+    let mut frame = vec![0xFE];
+    frame.extend_from_slice(&stuffed[..length]);
+    frame.push(0xFF);
+    frame
+}
+
+#[test]
+fn test_frame_truncated_header() {
+    assert_eq!(Frame::from_bytes(&[]), Err(DecodeError::FrameHeaderTruncated));
+    assert_eq!(Frame::from_bytes(&[0xFE]), Err(DecodeError::FrameHeaderTruncated));
+    assert_eq!(Frame::from_bytes(&[0xFE, 0xFF]), Err(DecodeError::FrameHeaderTruncated));
+    assert_eq!(Frame::from_bytes(&[0xFE, 0x00, 0x00, 0x01, 0xFF]), Err(DecodeError::FrameHeaderTruncated));
+    assert_eq!(Frame::from_bytes(&[0xFE, 0xFD, 0xFF]), Err(DecodeError::BadStuffing { position: 1 }));
+}
+
+#[test]
+fn test_frame_message_length_mismatch() {
+    // MEL announces 5 bytes, none present
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x05]);
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::MessageLengthMismatch { declared: 5, actual: 0 }));
+
+    // MEL announces 0 bytes, a PTY element follows
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x00, 0x07, 0x00, 0x00, 0x0A]);
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::MessageLengthMismatch { declared: 0, actual: 4 }));
+
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x04, 0x07, 0x00, 0x00, 0x0A]);
+    assert_eq!(Frame::from_bytes(&bytes).unwrap().elements.len(), 1);
+}
+
+#[test]
+fn test_frame_truncated_element() {
+    // RT element announcing 10 bytes of data within a 6-byte message
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x06, 0x0A, 0x00, 0x00, 0x0A, 0x00, 0x41]);
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::ElementLengthMismatch { mec: 0x0A, expected: 10, actual: 2 }));
+
+    // PI element cut after its PSN
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x03, 0x01, 0x00, 0x00]);
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::ElementLengthMismatch { mec: 0x01, expected: 2, actual: 0 }));
+
+    // PI element cut before its PSN
+    let bytes = frame_with_crc(&[0x00, 0x00, 0x01, 0x02, 0x01, 0x00]);
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::MessageTooShort));
+}
+
+#[test]
+fn test_element_truncated_header() {
+    assert_eq!(MessageElement::from_bytes(&[]), Err(DecodeError::MessageTooShort));
+    assert_eq!(MessageElement::from_bytes(&[0x01]), Err(DecodeError::MessageTooShort));
+    assert_eq!(MessageElement::from_bytes(&[0x0A, 0x00, 0x00]), Err(DecodeError::MessageTooShort));
+    assert_eq!(MessageElement::from_bytes(&[0xF0]), Err(DecodeError::UnknownElementType(0xF0)));
+}
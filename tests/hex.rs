@@ -0,0 +1,12 @@
+use uecp_rs::hex::*;
+
+#[test]
+fn test_parse_hex() {
+    assert_eq!(parse_hex("4BD7 16 00"), Ok(vec![0x4B, 0xD7, 0x16, 0x00]));
+    assert_eq!(parse_hex(" fe\n0a "), Ok(vec![0xFE, 0x0A]));
+    assert_eq!(parse_hex(""), Ok(vec![]));
+
+    assert_eq!(parse_hex("4BD"), Err(HexError::OddLength));
+    assert_eq!(parse_hex("4G"), Err(HexError::InvalidDigit('G')));
+    assert_eq!(parse_hex("+1"), Err(HexError::InvalidDigit('+')));
+}
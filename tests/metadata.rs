@@ -0,0 +1,149 @@
+use std::convert::TryFrom;
+use std::time::{ Duration, Instant };
+use uecp_rs::defs::*;
+use uecp_rs::elements::dab::*;
+use uecp_rs::elements::free_format::*;
+use uecp_rs::elements::radiotext::*;
+use uecp_rs::elements::tags::*;
+use uecp_rs::metadata::*;
+
+fn config() -> MetadataConfig {
+    MetadataConfig {
+        dataset_number: 1,
+        program_service_number: 2,
+        slogan: String::from("The best music"),
+        ps: String::from("RADIO 1"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_render() {
+    let event = NowPlaying::new("One More Time").artist("Daft Punk");
+
+    let text = render("Now: {artist} - {title}", &event, "").unwrap();
+    assert_eq!(text.text, "Now: Daft Punk - One More Time");
+    assert_eq!(text.tags, vec![
+        Tag { content_type: ContentType::ITEM_ARTIST, start: 5, length: 9 },
+        Tag { content_type: ContentType::ITEM_TITLE, start: 17, length: 13 }
+    ]);
+
+    assert_eq!(render("{title} ({album})", &event, ""), None);
+    assert_eq!(render("{genre}", &event, ""), None);
+    assert_eq!(render("{slogan}", &event, "On air").unwrap().text, "On air");
+}
+
+#[test]
+fn test_sanitize_and_pages() {
+    assert_eq!(sanitize("Don\u{2019}t Stop \u{2013} \u{65E5}\n", DabCharset::EbuLatin), "Don't Stop - ? ");
+    assert_eq!(sanitize("Caf\u{E9} \u{1F3B5}", DabCharset::Utf8), "Caf\u{E9} \u{1F3B5}");
+    assert_eq!(sanitize("Caf\u{E9} \u{1F3B5}", DabCharset::Ucs2), "Caf\u{E9} ?");
+
+    assert_eq!(ps_pages("Daft Punk One More Time"), vec!["Daft", "Punk One", "More", "Time"]);
+    assert_eq!(ps_pages("Supercalifragilistic"), vec!["Supercal", "ifragili", "stic"]);
+}
+
+#[test]
+fn test_now_playing() {
+    let mut bridge = MetadataBridge::new(MetadataConfig { dab_charset: Some(DabCharset::Utf8), ..config() });
+    let now = Instant::now();
+
+    let event = NowPlaying::new("One More Time").artist("Daft Punk").album("Discovery");
+    let elements = bridge.now_playing(&event, now).unwrap();
+    assert_eq!(elements.len(), 5);
+
+    let radiotext = RadioText::try_from(&elements[0]).unwrap();
+    assert_eq!(radiotext.text, "Daft Punk - One More Time");
+    assert_eq!((elements[0].dataset_number, elements[0].program_service_number), (1, 2));
+
+    let tags = RtPlusTags::from_free_format(&FreeFormat::try_from(&elements[1]).unwrap());
+    assert!(tags.item_running);
+    assert_eq!(tags.tags.len(), 2);
+
+    let label = DynamicLabel::try_from(&elements[2]).unwrap();
+    assert_eq!(label.text, "Daft Punk - One More Time - Discovery");
+    assert_eq!(DlPlusTags::try_from(&elements[3]).unwrap().tags.len(), 3);
+    // Without a dynamic PS template the static PS is sent
+    assert_eq!(elements[4].data, b"RADIO 1 ".to_vec());
+
+    // Each new text flips the A/B flag
+    let elements = bridge.now_playing(&NowPlaying::new("Digital Love"), now).unwrap();
+    let next = RadioText::try_from(&elements[0]).unwrap();
+    assert_eq!(next.text, "Digital Love");
+    assert_ne!(next.ab_flag, radiotext.ab_flag);
+}
+
+#[test]
+fn test_length_limits() {
+    let mut bridge = MetadataBridge::new(config());
+    let title = "A Very Long Title That Goes On And On And On Without End";
+    let event = NowPlaying::new(title).artist("Some Artist With A Long Name");
+
+    // Falls back to the title alone
+    let elements = bridge.now_playing(&event, Instant::now()).unwrap();
+    assert_eq!(RadioText::try_from(&elements[0]).unwrap().text, title);
+
+    // A tag over 32 characters can't be the second RT+ tag
+    let title = "Gimme! Gimme! Gimme! (A Man After Midnight)";
+    let elements = bridge.now_playing(&NowPlaying::new(title).artist("ABBA"), Instant::now()).unwrap();
+    let tags = RtPlusTags::from_free_format(&FreeFormat::try_from(&elements[1]).unwrap());
+    assert_eq!(tags.tags, vec![
+        Tag { content_type: ContentType::ITEM_TITLE, start: 7, length: 43 },
+        Tag { content_type: ContentType::ITEM_ARTIST, start: 0, length: 4 }
+    ]);
+
+    // The last template is cut when nothing fits
+    let long = "x".repeat(80);
+    let elements = bridge.now_playing(&NowPlaying::new(&long), Instant::now()).unwrap();
+    assert_eq!(RadioText::try_from(&elements[0]).unwrap().text, "x".repeat(64));
+    let tags = RtPlusTags::from_free_format(&FreeFormat::try_from(&elements[1]).unwrap());
+    assert_eq!(tags.tags, vec![Tag { content_type: ContentType::ITEM_TITLE, start: 0, length: 64 }]);
+}
+
+#[test]
+fn test_expiry_and_dynamic_ps() {
+    let mut bridge = MetadataBridge::new(MetadataConfig {
+        dynamic_ps_template: Some(String::from("{artist} {title}")),
+        ..config()
+    });
+    let start = Instant::now();
+
+    let event = NowPlaying::new("One More Time").artist("Daft Punk").duration(Duration::from_secs(10));
+    let elements = bridge.now_playing(&event, start).unwrap();
+    assert_eq!(elements[2].element_type, element_types::PS);
+    assert_eq!(elements[2].data, b"Daft    ".to_vec());
+    assert_eq!(bridge.expires(), Some(start + Duration::from_secs(10)));
+
+    assert!(bridge.poll(start + Duration::from_secs(1)).unwrap().is_empty());
+    let page = bridge.poll(start + Duration::from_secs(4)).unwrap();
+    assert_eq!(page[0].data, b"Punk One".to_vec());
+
+    // Back to the slogan and the static PS
+    let elements = bridge.poll(start + Duration::from_secs(10)).unwrap();
+    let radiotext = RadioText::try_from(&elements[0]).unwrap();
+    assert_eq!(radiotext.text, "The best music");
+    let tags = RtPlusTags::from_free_format(&FreeFormat::try_from(&elements[1]).unwrap());
+    assert!(!tags.item_running);
+    assert_eq!(elements[2].data, b"RADIO 1 ".to_vec());
+    assert_eq!(bridge.expires(), None);
+    assert!(bridge.poll(start + Duration::from_secs(20)).unwrap().is_empty());
+}
+
+#[test]
+fn test_dynamic_ps_falls_back_to_static_ps() {
+    let mut bridge = MetadataBridge::new(MetadataConfig {
+        dynamic_ps_template: Some(String::from("{artist} {title}")),
+        ..config()
+    });
+    let start = Instant::now();
+
+    let elements = bridge.now_playing(&NowPlaying::new("One More Time").artist("Daft Punk"), start).unwrap();
+    assert_eq!(elements[2].data, b"Daft    ".to_vec());
+
+    // The template can't be rendered without an artist, so the previous
+    // item's pages are replaced with the static PS
+    let elements = bridge.now_playing(&NowPlaying::new("Digital Love"), start + Duration::from_secs(1)).unwrap();
+    assert_eq!(elements[2].element_type, element_types::PS);
+    assert_eq!(elements[2].data, b"RADIO 1 ".to_vec());
+    assert!(bridge.poll(start + Duration::from_secs(10)).unwrap().is_empty());
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "profile")]
+
+use uecp_rs::defs::*;
+use uecp_rs::profile::*;
+use uecp_rs::protocol::*;
+
+const STATION: &str = r#"
+site = 62
+encoder = 14
+group_sequence = "0A 2A 0A 3A"
+
+[[service]]
+psn = 1
+pi = "D301"
+ps = "RADIO 1"
+pty = 10
+tp = true
+af = [98.5, 101.2]
+country = "DE"
+language = "de"
+
+[[service]]
+psn = 2
+pi = "D302"
+ps = "RADIO 2"
+eon = true
+"#;
+
+fn codes(elements: &[MessageElement]) -> Vec<(u8, u8)> {
+    elements.iter().map(|x| (x.element_type.code, x.program_service_number)).collect()
+}
+
+#[test]
+fn test_profile_compile() {
+    let profile = Profile::from_toml(STATION).unwrap();
+    let elements = profile.elements(3).unwrap();
+
+    assert_eq!(codes(&elements), vec![
+        (element_types::MAKE_PSN_LIST.code, 0),
+        (element_types::GROUP_SEQUENCE.code, 0),
+        (element_types::PI.code, 1),
+        (element_types::PS.code, 1),
+        (element_types::PTY.code, 1),
+        (element_types::TA_TP.code, 1),
+        (element_types::AF.code, 1),
+        (element_types::SLOW_LABELING.code, 1),
+        (element_types::SLOW_LABELING.code, 1),
+        (element_types::PI.code, 2),
+        (element_types::PS.code, 2),
+        (element_types::EON_ELEMENTS_TOGGLE.code, 2)
+    ]);
+    assert!(elements.iter().all(|x| x.dataset_number == 3));
+    assert_eq!(elements[0].data, vec![1, 2]);
+    assert_eq!(elements[3].data, b"RADIO 1 ".to_vec());
+    assert_eq!(elements[6].data, vec![0xE2, 110, 137]);
+    assert_eq!(elements[7].data, vec![0x00, 0xE0]);
+
+    let mut sequence = SequenceCounter::new();
+    let frames = profile.compile(3, &mut sequence).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].sequence_counter, 1);
+    assert_eq!((frames[0].site_address, frames[0].encoder_address), (62, 14));
+    assert_eq!(frames[0].elements, elements);
+}
+
+#[test]
+fn test_profile_validation() {
+    let invalid = |source: &str| Profile::from_toml(source).unwrap_err();
+
+    assert_eq!(
+        invalid("[[service]]\npsn = 1\npi = \"C2\""),
+        ProfileError::InvalidValue { psn: Some(1), field: "pi" }
+    );
+    assert_eq!(
+        invalid("[[service]]\npsn = 1\npi = \"C201\"\nps = \"TOO LONG PS\""),
+        ProfileError::InvalidValue { psn: Some(1), field: "ps" }
+    );
+    assert_eq!(
+        invalid("[[service]]\npsn = 1\npi = \"C201\"\naf = [87.0]"),
+        ProfileError::InvalidValue { psn: Some(1), field: "af" }
+    );
+    assert_eq!(
+        invalid("[[service]]\npsn = 1\npi = \"C201\"\n[[service]]\npsn = 1\npi = \"C202\""),
+        ProfileError::DuplicateService(1)
+    );
+    assert_eq!(
+        invalid("[[element]]\ntype = \"ODA_CONFIG\"\ndata = \"4BD7\""),
+        ProfileError::Encode(EncodeError::ElementLengthMismatch { mec: 0x40, expected: 7, actual: 2 })
+    );
+    assert_eq!(
+        invalid("group_sequence = \"0A 2A 3C\"").to_string(),
+        "invalid group_sequence: group 3 (\"3C\"): version must be A or B"
+    );
+    assert!(matches!(invalid("site = 62\nfrequency = 98.5"), ProfileError::Syntax(_)));
+    assert_eq!(invalid("site = 2000"), ProfileError::Encode(EncodeError::InvalidSiteAddress));
+}
+
+#[test]
+fn test_profile_diff() {
+    let mut current = Profile::from_toml(STATION).unwrap();
+    current.elements.push(RawElement { element_type: String::from("ODA_CONFIG"), data: String::from("4BD7 16 00 00 00 00") });
+    assert_eq!(current.diff(&current, 0).unwrap(), ProfileDiff::default());
+
+    let mut target = current.clone();
+    target.group_sequence = None;
+    target.services[0].ps = Some(String::from("RADIO 1X"));
+    target.services[0].pty = None;
+    target.services[0].af.clear();
+    target.services[0].language = None;
+    target.services.truncate(1);
+    target.elements.clear();
+
+    let diff = current.diff(&target, 0).unwrap();
+    assert_eq!(codes(&diff.elements), vec![
+        (element_types::MAKE_PSN_LIST.code, 0),
+        (element_types::PS.code, 1),
+        (element_types::GROUP_SEQUENCE.code, 0),
+        (element_types::PTY.code, 1),
+        (element_types::AF.code, 1),
+        (element_types::SLOW_LABELING.code, 1)
+    ]);
+    assert_eq!(diff.elements[0].data, vec![1]);
+    assert_eq!(diff.elements[1].data, b"RADIO 1X".to_vec());
+    // Dropped settings go back to their neutral value: no group sequence,
+    // PTY 0, an empty AF list and LIC 0
+    assert!(diff.elements[2].data.is_empty());
+    assert_eq!(diff.elements[3].data, vec![0x00]);
+    assert_eq!(diff.elements[4].data, vec![0xE0]);
+    assert_eq!(diff.elements[5].data, vec![0x30, 0x00]);
+
+    // Raw elements have no neutral value
+    assert_eq!(codes(&diff.unresettable), vec![(element_types::ODA_CONFIG.code, 0)]);
+
+    let (frames, unresettable) = current.diff_frames(&target, 0, &mut SequenceCounter::without_checking()).unwrap();
+    assert_eq!(frames[0].elements, diff.elements);
+    assert_eq!(unresettable, diff.unresettable);
+}
+
+#[test]
+fn test_profile_diff_drops_service_with_oda() {
+    let current = Profile::from_toml(&format!(
+        "{}\n[[service.element]]\ntype = \"ODA_CONFIG\"\ndata = \"4BD7 16 00 00 00 00\"\n", STATION
+    )).unwrap();
+    assert_eq!(current.diff(&current, 0).unwrap(), ProfileDiff::default());
+
+    let mut target = current.clone();
+    target.services.truncate(1);
+
+    // The encoder-wide ODA setting of the dropped service can't be cleared
+    let diff = current.diff(&target, 0).unwrap();
+    assert_eq!(codes(&diff.elements), vec![(element_types::MAKE_PSN_LIST.code, 0)]);
+    assert_eq!(codes(&diff.unresettable), vec![(element_types::ODA_CONFIG.code, 2)]);
+}
+
+#[test]
+fn test_profile_toml_roundtrip() {
+    let profile = Profile::from_toml(STATION).unwrap();
+    assert_eq!(Profile::from_toml(&profile.to_toml().unwrap()).unwrap(), profile);
+}
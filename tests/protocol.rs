@@ -1,10 +1,11 @@
 use uecp_rs::defs::*;
 use uecp_rs::protocol::*;
+use uecp_rs::logic::process_incoming_frame;
 
 #[test]
 fn test_compute_crc() {
     let source_bytes = "2D111234010105ABCD123F0XXXX11069212491000320066".as_bytes();
-    let result = Frame::compute_crc16_genibus(&source_bytes);
+    let result = Frame::compute_crc16_genibus(source_bytes);
     assert_eq!(result, 0x9723);
 }
 
@@ -20,7 +21,7 @@ fn test_revert_byte_stuffing() {
     let source = vec![0x12, 0xFD, 0x15, 0xFF, 0xAB, 0xFE, 0x26];
     let stuffed = Frame::apply_byte_stuffing(&source);
     assert_eq!(stuffed, vec![0x12, 0xFD, 0x00, 0x15, 0xFD, 0x02, 0xAB, 0xFD, 0x01, 0x26]);
-    let result = Frame::revert_byte_stuffing(&stuffed);
+    let result = Frame::revert_byte_stuffing(&stuffed).unwrap();
     assert_eq!(result, source);
 }
 
@@ -32,6 +33,12 @@ fn test_get_address_field() {
     assert_eq!(result, 0x5555);
 }
 
+#[test]
+fn test_get_address_field_out_of_range() {
+    assert_eq!(Frame::get_address_field(1024, 0), Err(EncodeError::InvalidSiteAddress));
+    assert_eq!(Frame::get_address_field(0, 64), Err(EncodeError::InvalidEncoderAddress));
+}
+
 #[test]
 fn test_build_pi_frame() {
     let frame = Frame {
@@ -131,3 +138,69 @@ fn test_frame_encode_and_decode_variable_length_element() {
     assert_eq!(decoded.elements[0].data, source.elements[0].data);
     assert_eq!(decoded.elements[0].data.len(), 5)
 }
+
+#[test]
+fn test_sequence_counter() {
+    let mut counter = SequenceCounter::new();
+    assert_eq!(counter.allocate(), 1);
+    assert_eq!(counter.allocate(), 2);
+
+    // 0 is reserved for "no sequence checking"
+    let mut counter = SequenceCounter::starting_at(255);
+    assert_eq!(counter.allocate(), 255);
+    assert_eq!(counter.allocate(), 1);
+
+    let mut counter = SequenceCounter::without_checking();
+    assert_eq!(counter.allocate(), 0);
+    assert_eq!(counter.allocate(), 0);
+
+    // Links don't share their numbering
+    let mut first = SequenceCounter::new();
+    let mut second = SequenceCounter::new();
+    assert_eq!(first.next_frame().sequence_counter, 1);
+    assert_eq!(first.next_frame().sequence_counter, 2);
+    assert_eq!(second.next_frame().sequence_counter, 1);
+
+    assert_eq!(Frame::with_sequence(42).sequence_counter, 42);
+    assert_eq!(Frame::new().sequence_counter, 0);
+}
+
+#[test]
+fn test_element_length_validation() {
+    let element = MessageElement::new(element_types::PI, &[0xC2, 0x01, 0x00, 0x00, 0x00]);
+    assert_eq!(element.into_bytes(), Err(EncodeError::ElementLengthMismatch { mec: 0x01, expected: 2, actual: 5 }));
+
+    let element = MessageElement::new(element_types::RT, &[0x41; 255]);
+    assert_eq!(element.into_bytes(), Err(EncodeError::ElementTooLarge));
+
+    // Trailing bytes after a fixed-length element
+    let bytes = [0x01, 0x00, 0x00, 0xC2, 0x01, 0x00];
+    assert_eq!(MessageElement::from_bytes(&bytes), Err(DecodeError::ElementLengthMismatch { mec: 0x01, expected: 2, actual: 3 }));
+    assert_eq!(MessageElement::from_bytes(&bytes[..5]).unwrap().data, vec![0xC2, 0x01]);
+
+    // MEL shorter and longer than the data present
+    let bytes = [0x0A, 0x00, 0x00, 0x02, 0x00, 0x41, 0x42];
+    assert_eq!(MessageElement::from_bytes(&bytes), Err(DecodeError::ElementLengthMismatch { mec: 0x0A, expected: 2, actual: 3 }));
+    let bytes = [0x0A, 0x00, 0x00, 0x04, 0x00, 0x41, 0x42];
+    assert_eq!(MessageElement::from_bytes(&bytes), Err(DecodeError::ElementLengthMismatch { mec: 0x0A, expected: 4, actual: 3 }));
+}
+
+#[test]
+fn test_ack_with_and_without_sequence() {
+    // A positive acknowledgement is the response code alone
+    let mut request = Frame::with_sequence(7);
+    request.set_addresses(62, 14);
+    let ok = process_incoming_frame(&request, |_| ResponseCode::Ok).unwrap();
+    assert_eq!(ok.elements[0].into_bytes().unwrap(), vec![0x18, 0x00]);
+    assert_eq!(Frame::from_bytes(&ok.into_bytes().unwrap()).unwrap(), ok);
+
+    let error = process_incoming_frame(&request, |_| ResponseCode::CRCError).unwrap();
+    assert_eq!(error.elements[0].into_bytes().unwrap(), vec![0x18, 0x01, 0x07]);
+    assert_eq!(Frame::from_bytes(&error.into_bytes().unwrap()).unwrap(), error);
+
+    assert_eq!(MessageElement::from_bytes(&[0x18, 0x00]).unwrap().data, vec![0x00]);
+    assert_eq!(MessageElement::from_bytes(&[0x18, 0x00, 0x07]).unwrap().data, vec![0x00, 0x07]);
+    assert_eq!(MessageElement::from_bytes(&[0x18]), Err(DecodeError::ElementLengthMismatch { mec: 0x18, expected: 1, actual: 0 }));
+    let element = MessageElement::new(element_types::UECP_ACK, &[0x00, 0x07, 0x00]);
+    assert_eq!(element.into_bytes(), Err(EncodeError::ElementLengthMismatch { mec: 0x18, expected: 2, actual: 3 }));
+}
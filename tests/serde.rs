@@ -0,0 +1,112 @@
+#![cfg(feature = "serde")]
+
+use uecp_rs::dataset::DatasetNumber;
+use uecp_rs::defs::*;
+use uecp_rs::elements::TypedElement;
+use uecp_rs::elements::dab::DynamicLabel;
+use uecp_rs::elements::pin::*;
+use uecp_rs::elements::radiotext::*;
+use uecp_rs::elements::signal::*;
+use uecp_rs::protocol::*;
+
+#[test]
+fn test_frame_roundtrip() {
+    let mut frame = Frame::with_sequence(12);
+    frame.set_addresses(62, 14);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    frame.elements.push(MessageElement::new(element_types::RT, &[0x00, 0x48, 0x49]));
+
+    let json = serde_json::to_string(&frame).unwrap();
+    assert!(json.contains("\"element_type\":\"PI\""));
+    assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), frame);
+}
+
+#[test]
+fn test_frame_element_text() {
+    let mut frame = Frame::new();
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    frame.elements.push(MessageElement::new(element_types::PS, b"RADIO 1 "));
+    frame.elements.push(MessageElement::new(element_types::RT, &[0x01, 0x43, 0x61, 0x66, 0x82]));
+    frame.elements.push(MessageElement::new(element_types::DAB_DL_MESSAGE, &[0x0F, 0x48, 0x69]));
+
+    let json = serde_json::to_value(&frame).unwrap();
+    let elements = json["elements"].as_array().unwrap();
+    assert!(elements[0].get("text").is_none());
+    assert_eq!(elements[1]["text"], "RADIO 1 ");
+    assert_eq!(elements[2]["text"], "Café");
+    assert_eq!(elements[2]["data"], serde_json::json!([0x01, 0x43, 0x61, 0x66, 0x82]));
+    assert_eq!(elements[3]["text"], "Hi");
+
+    // The data is what gets decoded, the text is there to be read
+    let mut edited = json.clone();
+    edited["elements"][1]["text"] = serde_json::json!("IGNORED");
+    assert_eq!(serde_json::from_value::<Frame>(edited).unwrap(), frame);
+}
+
+#[test]
+fn test_element_type_by_name_or_code() {
+    assert_eq!(serde_json::to_string(&element_types::PS).unwrap(), "\"PS\"");
+    assert_eq!(serde_json::from_str::<MessageElementType>("\"PS\"").unwrap(), element_types::PS);
+    assert_eq!(serde_json::from_str::<MessageElementType>("2").unwrap(), element_types::PS);
+
+    assert!(serde_json::from_str::<MessageElementType>("\"NOPE\"").is_err());
+    assert!(serde_json::from_str::<MessageElementType>("240").is_err());
+}
+
+#[test]
+fn test_radiotext_text_and_raw() {
+    let rt = RadioText::new("Café", true).unwrap();
+    let json = serde_json::to_value(&rt).unwrap();
+    assert_eq!(json["text"], "Café");
+    assert_eq!(json["raw"], serde_json::json!([0x43, 0x61, 0x66, 0x82]));
+    assert_eq!(serde_json::from_value::<RadioText>(json).unwrap(), rt);
+
+    // Raw bytes take precedence over the text
+    let decoded: RadioText = serde_json::from_str(r#"{"text":"ignored","raw":[72,73],"ab_flag":false}"#).unwrap();
+    assert_eq!(decoded.text, "HI");
+
+    let decoded: RadioText = serde_json::from_str(r#"{"text":"Hello"}"#).unwrap();
+    assert_eq!(decoded.text, "Hello");
+
+    // Checked like RadioText::new
+    let long = format!(r#"{{"text":"{}"}}"#, "x".repeat(65));
+    assert!(serde_json::from_str::<RadioText>(&long).is_err());
+    let long = format!(r#"{{"raw":{:?}}}"#, vec![0x41; 65]);
+    assert!(serde_json::from_str::<RadioText>(&long).is_err());
+    assert!(serde_json::from_str::<RadioText>(r#"{"text":"Hi","transmissions":16}"#).is_err());
+}
+
+#[test]
+fn test_validated_signal_and_label_fields() {
+    assert_eq!(serde_json::from_str::<RdsPhase>(r#"{"reference":1,"tenths_of_degree":900}"#).unwrap(), RdsPhase::new(1, 900).unwrap());
+    assert!(serde_json::from_str::<RdsPhase>(r#"{"reference":8,"tenths_of_degree":0}"#).is_err());
+    assert!(serde_json::from_str::<RdsPhase>(r#"{"reference":0,"tenths_of_degree":5000}"#).is_err());
+    assert!(serde_json::from_str::<RdsLevel>(r#"{"reference":0,"millivolts":9000}"#).is_err());
+    assert_eq!(serde_json::from_str::<ReferenceInput>("3").unwrap(), ReferenceInput::new(3).unwrap());
+    assert!(serde_json::from_str::<ReferenceInput>("8").is_err());
+
+    let long = format!(r#"{{"text":"{}","charset":"Utf8"}}"#, "x".repeat(129));
+    assert!(serde_json::from_str::<DynamicLabel>(&long).is_err());
+}
+
+#[test]
+fn test_typed_elements() {
+    let elements = vec![
+        TypedElement::Pty(PTY::RockMusic),
+        TypedElement::Pin(Pin::new(14, 9, 30).unwrap())
+    ];
+
+    let json = serde_json::to_string(&elements).unwrap();
+    let decoded: Vec<TypedElement> = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    assert_eq!(serde_json::to_string(&ResponseCode::CRCError).unwrap(), "\"CRCError\"");
+
+    // Pin fields are validated
+    assert!(serde_json::from_str::<Pin>(r#"{"day":14,"hour":25,"minute":0}"#).is_err());
+    assert_eq!(serde_json::from_str::<Pin>(r#"{"day":0,"hour":0,"minute":0}"#).unwrap(), Pin::NONE);
+
+    // A specific data set can't alias the special DSNs
+    assert_eq!(serde_json::from_str::<DatasetNumber>(r#"{"Specific":3}"#).unwrap(), DatasetNumber::specific(3).unwrap());
+    assert!(serde_json::from_str::<DatasetNumber>(r#"{"Specific":0}"#).is_err());
+    assert!(serde_json::from_str::<DatasetNumber>(r#"{"Specific":255}"#).is_err());
+}
@@ -0,0 +1,159 @@
+use std::any::Any;
+use std::convert::TryFrom;
+use uecp_rs::defs::*;
+use uecp_rs::protocol::*;
+use uecp_rs::elements::{ TypedElement, TypedMessageElement };
+use uecp_rs::elements::pin::Pin;
+use uecp_rs::elements::specific::*;
+
+const ACME_ID: u16 = 0x4143;
+
+#[derive(Debug, PartialEq)]
+struct AcmeGain {
+    gain: u8
+}
+
+impl SpecificCommand for AcmeGain {
+    fn manufacturer_id(&self) -> u16 {
+        ACME_ID
+    }
+
+    fn payload(&self) -> Result<Vec<u8>, EncodeError> {
+        Ok(vec![0x01, self.gain])
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+struct AcmeDecoder;
+
+impl SpecificCommandDecoder for AcmeDecoder {
+    fn manufacturer_id(&self) -> u16 {
+        ACME_ID
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Box<dyn SpecificCommand>, DecodeError> {
+        match payload {
+            [0x01, gain] => Ok(Box::new(AcmeGain { gain: *gain })),
+            _ => Err(DecodeError::InvalidElementData(element_types::SPECIFIC_COMMAND.code))
+        }
+    }
+}
+
+#[test]
+fn test_specific_command_encode() {
+    let element = specific_command_element(&AcmeGain { gain: 12 }).unwrap();
+    assert_eq!(element.into_bytes().unwrap(), vec![0x2D, 0x04, 0x41, 0x43, 0x01, 0x0C]);
+}
+
+#[test]
+fn test_specific_command_registry_decode() {
+    let mut registry = SpecificCommandRegistry::new();
+    assert!(registry.register(Box::new(AcmeDecoder)).is_none());
+    assert!(registry.is_registered(ACME_ID));
+
+    let element = specific_command_element(&AcmeGain { gain: 12 }).unwrap();
+    let decoded = registry.decode(&element).unwrap();
+    assert_eq!(decoded.manufacturer_id(), ACME_ID);
+    assert_eq!(decoded.downcast_ref::<AcmeGain>(), Some(&AcmeGain { gain: 12 }));
+    assert_eq!(MessageElement::try_from(&decoded).unwrap(), element);
+
+    let invalid = MessageElement::new(element_types::SPECIFIC_COMMAND, &[0x41, 0x43, 0x02]);
+    assert_eq!(registry.decode(&invalid).err(), Some(DecodeError::InvalidElementData(0x2D)));
+}
+
+#[test]
+fn test_specific_command_unknown_vendor() {
+    let registry = SpecificCommandRegistry::new();
+    let element = MessageElement::new(element_types::SPECIFIC_COMMAND, &[0x12, 0x34, 0xAA, 0xBB]);
+
+    match registry.decode(&element).unwrap() {
+        SpecificCommandElement::Raw { manufacturer_id, payload } => {
+            assert_eq!(manufacturer_id, 0x1234);
+            assert_eq!(payload, vec![0xAA, 0xBB]);
+        },
+        other => panic!("unexpected {:?}", other)
+    }
+}
+
+fn typed_values(frame: &Frame, registry: Option<&SpecificCommandRegistry>) -> Vec<TypedMessageElement> {
+    frame.typed_elements(registry).into_iter().map(|x| x.unwrap()).collect()
+}
+
+#[test]
+fn test_frame_typed_elements() {
+    let mut registry = SpecificCommandRegistry::new();
+    registry.register(Box::new(AcmeDecoder));
+
+    let source = Frame {
+        sequence_counter: 1,
+        site_address: 0,
+        encoder_address: 0,
+        elements: vec![
+            MessageElement::from(PTY::News),
+            specific_command_element(&AcmeGain { gain: 3 }).unwrap(),
+            MessageElement::new(element_types::PI, &[0xC2, 0x01])
+        ]
+    };
+
+    let decoded = Frame::from_bytes(&source.into_bytes().unwrap()).unwrap();
+    let typed = typed_values(&decoded, Some(&registry));
+
+    assert!(matches!(typed[0].value, TypedElement::Pty(PTY::News)));
+    match &typed[1].value {
+        TypedElement::SpecificCommand(command) => assert!(command.downcast_ref::<AcmeGain>().is_some()),
+        other => panic!("unexpected {:?}", other)
+    }
+    assert!(matches!(typed[2].value, TypedElement::Raw(_)));
+
+    for (typed, element) in typed.iter().zip(source.elements.iter()) {
+        assert_eq!(&typed.into_element().unwrap(), element);
+    }
+
+    let without_registry = typed_values(&decoded, None);
+    assert!(matches!(without_registry[1].value, TypedElement::SpecificCommand(SpecificCommandElement::Raw { .. })));
+}
+
+#[test]
+fn test_typed_elements_keep_dsn_and_psn() {
+    let mut pty = MessageElement::from(PTY::News);
+    pty.set_dsn_psn(2, 3);
+    let mut pin = MessageElement::from(Pin::new(1, 8, 0).unwrap());
+    pin.set_dsn_psn(0, 3);
+
+    let mut frame = Frame::new();
+    frame.elements = vec![pty, pin.clone()];
+
+    let decoded = Frame::from_bytes(&frame.into_bytes().unwrap()).unwrap();
+    let mut typed = typed_values(&decoded, None);
+    assert_eq!((typed[0].dataset_number, typed[0].program_service_number), (2, 3));
+
+    // Editing the value keeps the command aimed at the EON service
+    typed[0].value = TypedElement::Pty(PTY::RockMusic);
+    let mut expected = MessageElement::from(PTY::RockMusic);
+    expected.set_dsn_psn(2, 3);
+    assert_eq!(typed[0].into_element().unwrap(), expected);
+    assert_eq!(typed[1].into_element().unwrap(), pin);
+
+    assert_eq!(TypedMessageElement::new(TypedElement::Pty(PTY::News)).into_element().unwrap(), MessageElement::from(PTY::News));
+}
+
+#[test]
+fn test_typed_elements_with_invalid_data() {
+    let mut frame = Frame::new();
+    frame.elements = vec![
+        MessageElement::from(PTY::News),
+        // DI is 4 bits
+        MessageElement::new(element_types::DI, &[0x10]),
+        MessageElement::new(element_types::PI, &[0xC2, 0x01])
+    ];
+
+    let decoded = Frame::from_bytes(&frame.into_bytes().unwrap()).unwrap();
+    let typed = decoded.typed_elements(None);
+    assert_eq!(typed.len(), 3);
+    assert!(matches!(typed[0].as_ref().unwrap().value, TypedElement::Pty(PTY::News)));
+    assert_eq!(typed[1].as_ref().unwrap_err(), &DecodeError::InvalidElementData(element_types::DI.code));
+    assert!(matches!(typed[2].as_ref().unwrap().value, TypedElement::Raw(_)));
+}
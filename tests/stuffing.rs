@@ -0,0 +1,85 @@
+use proptest::prelude::*;
+use uecp_rs::defs::*;
+use uecp_rs::framing::*;
+use uecp_rs::protocol::*;
+
+#[test]
+fn test_unstuff_bad_sequences() {
+    let mut out = [0u8; 16];
+
+    // Frame ending in the middle of an escape
+    assert_eq!(unstuff_into(&[0x12, 0x34, 0xFD], &mut out), Err(DecodeError::BadStuffing { position: 2 }));
+    // Escape of a value that doesn't need one
+    assert_eq!(unstuff_into(&[0x12, 0xFD, 0x03], &mut out), Err(DecodeError::BadStuffing { position: 1 }));
+    // Unstuffed STA or STP
+    assert_eq!(unstuff_into(&[0xFE, 0x12], &mut out), Err(DecodeError::BadStuffing { position: 0 }));
+    assert_eq!(unstuff_into(&[0x12, 0xFF], &mut out), Err(DecodeError::BadStuffing { position: 1 }));
+
+    assert_eq!(Frame::revert_byte_stuffing(&[0xFD]), Err(DecodeError::BadStuffing { position: 0 }));
+}
+
+#[test]
+fn test_unstuff_lenient() {
+    let mut out = [0u8; 16];
+
+    let length = unstuff_into_with(&[0x12, 0xFD, 0x03, 0xFD, 0x01, 0xFD], &mut out, StuffingMode::Lenient).unwrap();
+    assert_eq!(&out[..length], &[0x12, 0xFD, 0x03, 0xFE, 0xFD]);
+
+    let length = unstuff_into_with(&[0xFF, 0x12], &mut out, StuffingMode::Lenient).unwrap();
+    assert_eq!(&out[..length], &[0xFF, 0x12]);
+}
+
+#[test]
+fn test_frame_bad_stuffing() {
+    let mut frame = Frame::with_sequence(1);
+    frame.elements.push(MessageElement::new(element_types::PI, &[0xC2, 0x01]));
+    let mut bytes = frame.into_bytes().unwrap();
+
+    // 0xFD 0x03 right after STA, ADD and SEQ
+    bytes.splice(4..4, [0xFD, 0x03].iter().cloned());
+    assert_eq!(Frame::from_bytes(&bytes), Err(DecodeError::BadStuffing { position: 4 }));
+
+    // Lenient decoding gets as far as the CRC check
+    assert_eq!(Frame::from_bytes_with(&bytes, StuffingMode::Lenient), Err(DecodeError::CRCError));
+}
+
+proptest! {
+    #[test]
+    fn fuzz_stuffing_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..300)) {
+        let stuffed = Frame::apply_byte_stuffing(&data);
+        prop_assert!(stuffed.iter().all(|x| *x < 0xFE));
+        prop_assert_eq!(Frame::revert_byte_stuffing(&stuffed), Ok(data));
+    }
+
+    #[test]
+    fn fuzz_unstuff_strict(data in proptest::collection::vec(any::<u8>(), 0..300)) {
+        let mut out = vec![0u8; data.len()];
+        match unstuff_into(&data, &mut out) {
+            Ok(length) => prop_assert_eq!(Frame::apply_byte_stuffing(&out[..length]), data),
+            Err(DecodeError::BadStuffing { position }) => {
+                prop_assert!(data[position] >= 0xFD);
+            },
+            Err(e) => prop_assert!(false, "unexpected error {:?}", e)
+        }
+    }
+
+    #[test]
+    fn fuzz_unstuff_lenient(data in proptest::collection::vec(any::<u8>(), 0..300)) {
+        let mut lenient = vec![0u8; data.len()];
+        let lenient_length = unstuff_into_with(&data, &mut lenient, StuffingMode::Lenient);
+        prop_assert!(lenient_length.is_ok());
+
+        // Both modes agree whenever the input is valid
+        let mut strict = vec![0u8; data.len()];
+        if let Ok(length) = unstuff_into(&data, &mut strict) {
+            prop_assert_eq!(&strict[..length], &lenient[..lenient_length.unwrap()]);
+        }
+    }
+
+    #[test]
+    fn fuzz_unstuff_small_buffer(data in proptest::collection::vec(any::<u8>(), 0..64), size in 0usize..64) {
+        let mut out = vec![0u8; size];
+        let _ = unstuff_into_with(&data, &mut out, StuffingMode::Strict);
+        let _ = unstuff_into_with(&data, &mut out, StuffingMode::Lenient);
+    }
+}
@@ -0,0 +1,49 @@
+use uecp_rs::defs::*;
+use uecp_rs::protocol::*;
+use uecp_rs::elements::flags::*;
+use uecp_rs::validation::*;
+
+#[test]
+fn test_validate_ta_without_tp() {
+    let mut element = MessageElement::from(TrafficFlags::new(false, true));
+    element.program_service_number = 2;
+
+    let warnings = validate_elements(&[element]);
+    assert_eq!(warnings, vec![ValidationWarning::TaWithoutTp { dsn: 0, psn: 2 }]);
+}
+
+#[test]
+fn test_validate_reserved_bits() {
+    let frame = Frame {
+        sequence_counter: 1,
+        site_address: 0,
+        encoder_address: 0,
+        elements: vec![
+            MessageElement::new(element_types::MS, &[0x02]),
+            MessageElement::from(DecoderIdentification::default())
+        ]
+    };
+
+    let warnings = validate_frame(&frame);
+    assert_eq!(warnings, vec![ValidationWarning::ReservedBitsSet { mec: 0x05, dsn: 0, psn: 0 }]);
+}
+
+#[test]
+fn test_validate_consistent_elements() {
+    let elements = vec![
+        MessageElement::from(TrafficFlags::TrafficAnnouncement),
+        MessageElement::from(MusicSpeech::Speech)
+    ];
+
+    assert!(validate_elements(&elements).is_empty());
+}
+
+#[test]
+fn test_validate_alarm_without_ta() {
+    let elements = vec![
+        MessageElement::from(PTY::Alarm),
+        MessageElement::from(TrafficFlags::TrafficProgramme)
+    ];
+
+    assert_eq!(validate_elements(&elements), vec![ValidationWarning::AlarmWithoutTa { dsn: 0, psn: 0 }]);
+}